@@ -0,0 +1,299 @@
+// Abstract syntax tree for DA programs
+// The parser builds a Program out of these types, which the checker and the code generators then walk
+
+use std::collections::HashMap;
+use std::fmt;
+
+// Location struct stores the file, line, and column where a token or a program item starts
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+// Locations are displayed as 'file:line:column', the form editors and terminals recognize
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+// DataType enum will be used to store the declared type of a data entry
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    Vector,
+    Number,
+    Table,
+}
+
+// DataDef struct stores one 'id : type' entry of the data section
+// An entry may be given its value where it is declared, e.g. 'threshold : number = 0.05'
+pub struct DataDef {
+    pub id: String,
+    pub data_type: DataType,
+    pub init: Option<Expr>,
+    pub location: Location,
+}
+
+// Const struct stores one 'const id = literal' entry of the data section
+// The value is kept as its lexeme: a number, a STRING lexeme (quotes included), 'true' or 'false'
+pub struct Const {
+    pub id: String,
+    pub value: String,
+    pub location: Location,
+}
+
+// Define struct stores one user function of the define section, e.g. 'cv(v) = stddev(v) / mean(v)'
+pub struct Define {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Expr,
+    pub location: Location,
+}
+
+// Source enum stores which loader an input op uses and its arguments
+// File names, fields and delimiters are kept as STRING lexemes (quotes included)
+#[derive(PartialEq)]
+pub enum Source {
+    Csv {
+        file: String,
+        header: bool,
+        column: Column,
+        delimiter: Option<String>,
+    },
+    Json {
+        file: String,
+        field: Option<String>,
+    },
+    Lines {
+        file: String,
+    },
+    Table {
+        file: String,
+        header: bool,
+    },
+}
+
+// InputOp struct stores one 'id = read...(...)' entry of the input section
+pub struct InputOp {
+    pub id: String,
+    pub source: Source,
+    pub location: Location,
+}
+
+// Expr enum stores the right-hand side of process ops and the bodies of user functions
+// Binary holds one of the arithmetic operators '+', '-', '*' or '/', applied element-wise to vectors
+// Filter holds the vector, the comparison lexeme and the threshold of 'filter(x, > 0)'
+// Column holds the table and the column of 't.revenue' or 't[2]'
+#[derive(Clone, PartialEq)]
+pub enum Expr {
+    Num(String),
+    Id(String),
+    Call(String, Vec<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Filter(Box<Expr>, String, Box<Expr>),
+    Column(Box<Expr>, Column),
+}
+
+// Column enum stores how a column of a table or a CSV file is picked: by its header name or by its index
+// A name is kept as it is written between the quotes
+#[derive(Clone, PartialEq)]
+pub enum Column {
+    Name(String),
+    Index(usize),
+}
+
+// Columns are displayed the way they are written in a read: a name in quotes, an index as a number
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Column::Name(name) => write!(f, "\"{}\"", name),
+            Column::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+// ProcessOp struct stores one 'id = expression' entry of the process section
+// A call with several results assigns them to several identifiers, e.g. 'a, b, rsq = regression(x, y)'
+pub struct ProcessOp {
+    pub ids: Vec<String>,
+    pub expr: Expr,
+    pub location: Location,
+}
+
+// OutputOp enum stores one entry of the output section: a STRING lexeme or an identifier on its own line,
+// a print statement with several items on one line, a write/append statement with one line for a file,
+// or a plot/histogram statement with the vectors to draw and the SVG file to draw them in
+pub enum OutputOp {
+    Str(String),
+    Id(String),
+    Print(Vec<PrintItem>),
+    Write {
+        file: String,
+        items: Vec<PrintItem>,
+        append: bool,
+    },
+    Plot {
+        x: String,
+        y: String,
+        file: String,
+    },
+    Histogram {
+        values: String,
+        file: String,
+    },
+}
+
+// PrintItem enum stores one item of a print, write or append statement
+// An identifier may carry the number of decimals it is printed with, e.g. 'a:3'
+pub enum PrintItem {
+    Str(String),
+    Id(String, Option<usize>),
+}
+
+// Program struct stores every section of a parsed DA program
+// Each output op is stored with the Location it starts at
+#[derive(Default)]
+pub struct Program {
+    pub consts: Vec<Const>,
+    pub datadefs: Vec<DataDef>,
+    pub defines: Vec<Define>,
+    pub inputops: Vec<InputOp>,
+    pub processops: Vec<ProcessOp>,
+    pub outputops: Vec<(OutputOp, Location)>,
+}
+
+// Helper function
+// Takes a Const and returns whether its value is a number, the only kind of constant expressions may use
+pub fn is_numeric(constant: &Const) -> bool {
+    return !constant.value.starts_with('"')
+        && constant.value != "true"
+        && constant.value != "false";
+}
+
+// Helper function
+// Takes an expression, a list of parameter names and a list of argument expressions
+// Returns a copy of the expression with every parameter replaced by its argument
+pub fn substitute(expr: &Expr, params: &[String], args: &[Expr]) -> Expr {
+    match expr {
+        Expr::Num(num) => Expr::Num(num.clone()),
+        Expr::Id(id) => match params.iter().position(|param| param == id) {
+            Some(i) => args[i].clone(),
+            None => Expr::Id(id.clone()),
+        },
+        Expr::Call(func, call_args) => Expr::Call(
+            func.clone(),
+            call_args
+                .iter()
+                .map(|arg| substitute(arg, params, args))
+                .collect(),
+        ),
+        Expr::Binary(op, lhs, rhs) => Expr::Binary(
+            *op,
+            Box::new(substitute(lhs, params, args)),
+            Box::new(substitute(rhs, params, args)),
+        ),
+        Expr::Negate(operand) => Expr::Negate(Box::new(substitute(operand, params, args))),
+        Expr::Filter(vector, comparison, threshold) => Expr::Filter(
+            Box::new(substitute(vector, params, args)),
+            comparison.clone(),
+            Box::new(substitute(threshold, params, args)),
+        ),
+        Expr::Column(table, column) => {
+            Expr::Column(Box::new(substitute(table, params, args)), column.clone())
+        }
+    }
+}
+
+// Helper function
+// Takes an expression and the user functions by name
+// Returns a copy of the expression where every user function call is replaced by the function body
+pub fn inline_calls(expr: &Expr, functions: &HashMap<String, &Define>) -> Expr {
+    match expr {
+        Expr::Call(func, args) => {
+            let args: Vec<Expr> = args
+                .iter()
+                .map(|arg| inline_calls(arg, functions))
+                .collect();
+            match functions.get(func) {
+                Some(define) => {
+                    inline_calls(&substitute(&define.body, &define.params, &args), functions)
+                }
+                None => Expr::Call(func.clone(), args),
+            }
+        }
+        Expr::Binary(op, lhs, rhs) => Expr::Binary(
+            *op,
+            Box::new(inline_calls(lhs, functions)),
+            Box::new(inline_calls(rhs, functions)),
+        ),
+        Expr::Negate(operand) => Expr::Negate(Box::new(inline_calls(operand, functions))),
+        Expr::Filter(vector, comparison, threshold) => Expr::Filter(
+            Box::new(inline_calls(vector, functions)),
+            comparison.clone(),
+            Box::new(inline_calls(threshold, functions)),
+        ),
+        Expr::Column(table, column) => {
+            Expr::Column(Box::new(inline_calls(table, functions)), column.clone())
+        }
+        _ => expr.clone(),
+    }
+}
+
+// Helper function
+// Takes an expression and a list, and adds every identifier the expression refers to to the list
+// Identifiers inside the bodies of called user functions are not included
+pub fn identifiers(expr: &Expr, ids: &mut Vec<String>) {
+    match expr {
+        Expr::Num(_) => {}
+        Expr::Id(id) => {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        Expr::Call(_, args) => {
+            for arg in args {
+                identifiers(arg, ids);
+            }
+        }
+        Expr::Binary(_, lhs, rhs) => {
+            identifiers(lhs, ids);
+            identifiers(rhs, ids);
+        }
+        Expr::Negate(operand) => identifiers(operand, ids),
+        Expr::Filter(vector, _, threshold) => {
+            identifiers(vector, ids);
+            identifiers(threshold, ids);
+        }
+        Expr::Column(table, _) => identifiers(table, ids),
+    }
+}
+
+// Helper function
+// Takes an output op and a list, and adds every identifier the output op prints or draws to the list
+pub fn output_identifiers(outputop: &OutputOp, ids: &mut Vec<String>) {
+    let items: &[PrintItem] = match outputop {
+        OutputOp::Id(id) => {
+            ids.push(id.clone());
+            return;
+        }
+        OutputOp::Plot { x, y, .. } => {
+            ids.push(x.clone());
+            ids.push(y.clone());
+            return;
+        }
+        OutputOp::Histogram { values, .. } => {
+            ids.push(values.clone());
+            return;
+        }
+        OutputOp::Str(_) => return,
+        OutputOp::Print(items) | OutputOp::Write { items, .. } => items,
+    };
+    for item in items {
+        if let PrintItem::Id(id, _) = item {
+            ids.push(id.clone());
+        }
+    }
+}
@@ -0,0 +1,665 @@
+// Semantic checker for DA programs
+// Runs after a successful parse and panics on the first semantic error, just like the lexer and parser
+
+use crate::ast::{
+    identifiers, is_numeric, Const, DataType, Define, Expr, Location, OutputOp, PrintItem, Program,
+    Source,
+};
+use crate::diagnostics::did_you_mean;
+use std::collections::HashMap;
+
+// Signatures type stores the argument types each user function is called with
+// DA functions are monomorphic, so the code generators can rely on one set of parameter types per function
+pub type Signatures = HashMap<String, Vec<DataType>>;
+
+// Helper function
+// Returns the argument types and the result type of every statistics function
+pub fn builtin_signatures() -> HashMap<&'static str, (Vec<DataType>, DataType)> {
+    return HashMap::from([
+        (
+            "regressiona",
+            (vec![DataType::Vector, DataType::Vector], DataType::Number),
+        ),
+        (
+            "regressionb",
+            (vec![DataType::Vector, DataType::Vector], DataType::Number),
+        ),
+        (
+            "correlation",
+            (vec![DataType::Vector, DataType::Vector], DataType::Number),
+        ),
+        ("mean", (vec![DataType::Vector], DataType::Number)),
+        ("stddev", (vec![DataType::Vector], DataType::Number)),
+        ("abs", (vec![DataType::Vector], DataType::Vector)),
+        ("log", (vec![DataType::Vector], DataType::Vector)),
+        (
+            "slice",
+            (
+                vec![DataType::Vector, DataType::Number, DataType::Number],
+                DataType::Vector,
+            ),
+        ),
+        (
+            "concat",
+            (vec![DataType::Vector, DataType::Vector], DataType::Vector),
+        ),
+        (
+            "regression",
+            (vec![DataType::Table, DataType::Vector], DataType::Vector),
+        ),
+    ]);
+}
+
+// Helper function
+// Takes a statistics function and its argument types
+// Returns the types of its results if the call has several results, e.g. the intercept, slope and squared correlation of
+// 'regression(x, y)' with two vectors, which are assigned together as in 'a, b, rsq = regression(x, y)'
+pub fn multiple_results(func: &str, arg_types: &[DataType]) -> Option<Vec<DataType>> {
+    if func == "regression" && arg_types == [DataType::Vector, DataType::Vector] {
+        return Some(vec![DataType::Number, DataType::Number, DataType::Number]);
+    }
+    return None;
+}
+
+// Helper function
+// Takes a DataType and returns its name as written in the data section
+pub fn type_name(data_type: DataType) -> &'static str {
+    return match data_type {
+        DataType::Vector => "vector",
+        DataType::Number => "number",
+        DataType::Table => "table",
+    };
+}
+
+// Define Checker
+// Checks that a user function only refers to its own parameters, to statistics functions, and to user
+// functions defined before it, and that every call has the right number of arguments
+// Takes the Define, the functions defined so far, and the expression to check (initially the body)
+// Function panics if semantic errors are found
+fn define_checker(define: &Define, functions: &HashMap<String, &Define>, expr: &Expr) {
+    match expr {
+        Expr::Num(_) => {}
+        Expr::Id(id) => {
+            assert!(
+                define.params.contains(id),
+                "\n\n; SEMANTIC ERROR[E0003]!\n; {}: Unknown identifier '{}' in the definition of '{}'.\n{}\n",
+                define.location,
+                id,
+                define.name,
+                did_you_mean(id, define.params.iter().map(String::as_str))
+            );
+        }
+        Expr::Call(func, args) => {
+            let arity: usize = if let Some((params, _)) = builtin_signatures().get(func.as_str()) {
+                params.len()
+            } else if let Some(callee) = functions.get(func) {
+                callee.params.len()
+            } else {
+                panic!(
+                    "\n\n; SEMANTIC ERROR[E0009]!\n; {}: Unknown function '{}' in the definition of '{}'.\n{}\n",
+                    define.location,
+                    func,
+                    define.name,
+                    unknown_function_help(func, functions)
+                );
+            };
+            assert!(
+                args.len() == arity,
+                "\n\n; SEMANTIC ERROR[E0010]!\n; {}: Function '{}' expects {} argument(s) but was given {}.\n\n",
+                define.location,
+                func,
+                arity,
+                args.len()
+            );
+            for arg in args {
+                define_checker(define, functions, arg);
+            }
+        }
+        Expr::Binary(_, lhs, rhs) => {
+            define_checker(define, functions, lhs);
+            define_checker(define, functions, rhs);
+        }
+        Expr::Negate(operand) => define_checker(define, functions, operand),
+        Expr::Filter(vector, _, threshold) => {
+            define_checker(define, functions, vector);
+            define_checker(define, functions, threshold);
+        }
+        Expr::Column(table, _) => define_checker(define, functions, table),
+    }
+}
+
+// Expression Checker
+// Computes the type of an expression, checking arities and argument types on the way
+// Calls to user functions are checked by checking their body with the parameters bound to the argument types
+// Arithmetic on a vector is element-wise and yields a vector
+// Takes the expression, the types of the identifiers in scope, the user functions, the Signatures seen so far,
+// and the Location of the statement holding the expression
+// Returns the DataType of the expression
+// Function panics if semantic errors are found
+fn expression_checker(
+    expr: &Expr,
+    scope: &HashMap<String, DataType>,
+    functions: &HashMap<String, &Define>,
+    signatures: &mut Signatures,
+    location: &Location,
+) -> DataType {
+    match expr {
+        Expr::Num(_) => return DataType::Number,
+        Expr::Id(id) => match scope.get(id) {
+            Some(data_type) => return *data_type,
+            None => panic!("{}", undeclared_error(id, scope, location, None)),
+        },
+        Expr::Call(func, args) => {
+            let arg_types: Vec<DataType> = args
+                .iter()
+                .map(|arg| expression_checker(arg, scope, functions, signatures, location))
+                .collect();
+
+            if let Some(results) = multiple_results(func, &arg_types) {
+                panic!(
+                    "\n\n; SEMANTIC ERROR[E0014]!\n; {}: '{}' returns {} values here, which must be assigned on their own, e.g. 'a, b, rsq = {}(x, y)'.\n\n",
+                    location,
+                    func,
+                    results.len(),
+                    func
+                );
+            }
+
+            if let Some((params, result)) = builtin_signatures().get(func.as_str()) {
+                assert!(
+                    arg_types.len() == params.len(),
+                    "\n\n; SEMANTIC ERROR[E0010]!\n; {}: Function '{}' expects {} argument(s) but was given {}.\n\n",
+                    location,
+                    func,
+                    params.len(),
+                    arg_types.len()
+                );
+                for (i, param) in params.iter().enumerate() {
+                    assert!(
+                        arg_types[i] == *param,
+                        "\n\n; SEMANTIC ERROR[E0011]!\n; {}: Argument {} of '{}' must be a {}, found a {}.\n\n",
+                        location,
+                        i + 1,
+                        func,
+                        type_name(*param),
+                        type_name(arg_types[i])
+                    );
+                }
+                return *result;
+            }
+
+            let define: &Define = match functions.get(func) {
+                Some(define) => define,
+                None => panic!(
+                    "\n\n; SEMANTIC ERROR[E0009]!\n; {}: Unknown function '{}'.\n{}\n",
+                    location,
+                    func,
+                    unknown_function_help(func, functions)
+                ),
+            };
+            assert!(
+                arg_types.len() == define.params.len(),
+                "\n\n; SEMANTIC ERROR[E0010]!\n; {}: Function '{}' expects {} argument(s) but was given {}.\n\n",
+                location,
+                func,
+                define.params.len(),
+                arg_types.len()
+            );
+            if let Some(param_types) = signatures.get(func) {
+                assert!(
+                    *param_types == arg_types,
+                    "\n\n; SEMANTIC ERROR[E0016]!\n; {}: Function '{}' is called with ({}) but was called with ({}) before.\n\n",
+                    location,
+                    func,
+                    arg_types.iter().map(|t| type_name(*t)).collect::<Vec<&str>>().join(", "),
+                    param_types.iter().map(|t| type_name(*t)).collect::<Vec<&str>>().join(", ")
+                );
+            }
+            signatures.insert(func.clone(), arg_types.clone());
+
+            let mut body_scope: HashMap<String, DataType> = HashMap::new();
+            for (i, param) in define.params.iter().enumerate() {
+                body_scope.insert(param.clone(), arg_types[i]);
+            }
+            return expression_checker(&define.body, &body_scope, functions, signatures, location);
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs_type: DataType =
+                expression_checker(lhs, scope, functions, signatures, location);
+            let rhs_type: DataType =
+                expression_checker(rhs, scope, functions, signatures, location);
+            assert!(
+                lhs_type != DataType::Table && rhs_type != DataType::Table,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: Operator '{}' cannot be applied to a table, use one of its columns instead.\n\n",
+                location,
+                op
+            );
+            if lhs_type == DataType::Number && rhs_type == DataType::Number {
+                return DataType::Number;
+            }
+            return DataType::Vector;
+        }
+        Expr::Negate(operand) => {
+            let operand_type: DataType =
+                expression_checker(operand, scope, functions, signatures, location);
+            assert!(
+                operand_type != DataType::Table,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: Operator '-' cannot be applied to a table, use one of its columns instead.\n\n",
+                location
+            );
+            return operand_type;
+        }
+        Expr::Filter(vector, comparison, threshold) => {
+            let vector_type: DataType =
+                expression_checker(vector, scope, functions, signatures, location);
+            let threshold_type: DataType =
+                expression_checker(threshold, scope, functions, signatures, location);
+            assert!(
+                vector_type == DataType::Vector && threshold_type == DataType::Number,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: 'filter' expects a vector and '{} number', found a {} and '{} {}'.\n\n",
+                location,
+                comparison,
+                type_name(vector_type),
+                comparison,
+                type_name(threshold_type)
+            );
+            return DataType::Vector;
+        }
+        Expr::Column(table, _) => {
+            let table_type: DataType =
+                expression_checker(table, scope, functions, signatures, location);
+            assert!(
+                table_type == DataType::Table,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: Columns can only be taken from a table, found a {}.\n\n",
+                location,
+                type_name(table_type)
+            );
+            return DataType::Vector;
+        }
+    }
+}
+
+// Helper function
+// Computes the type of an expression the checker has already accepted, for use by the code generators
+// Takes the expression, the types of the identifiers in scope, and the user functions
+// Returns the DataType of the expression
+pub fn expression_type(
+    expr: &Expr,
+    scope: &HashMap<String, DataType>,
+    functions: &HashMap<String, &Define>,
+) -> DataType {
+    return expression_checker(
+        expr,
+        scope,
+        functions,
+        &mut Signatures::new(),
+        &Location::default(),
+    );
+}
+
+// Helper function
+// Takes an identifier that is not declared, the declared types, the Location of the statement using it, and the
+// type it would have to be declared with, if it is known
+// Returns the message to panic with, which suggests a declared identifier close to it, e.g. 'xvalues' for
+// 'xvalue', and how to declare it
+fn undeclared_error(
+    id: &str,
+    declared: &HashMap<String, DataType>,
+    location: &Location,
+    data_type: Option<&str>,
+) -> String {
+    let example: String = match data_type {
+        Some(data_type) => format!(", e.g. '{} : {}'", id, data_type),
+        None => String::new(),
+    };
+    return format!(
+        "\n\n; SEMANTIC ERROR[E0003]!\n; {}: Identifier '{}' is not declared in the data section.\n{}; help: declare it in the data section with its type{}\n\n",
+        location,
+        id,
+        did_you_mean(id, declared.keys().map(String::as_str)),
+        example
+    );
+}
+
+// Helper function
+// Takes the name of a function that is not known and the user functions
+// Returns a help line naming the statistics or user function close to it, e.g. 'correlation' for 'corelation', or
+// else nothing
+fn unknown_function_help(func: &str, functions: &HashMap<String, &Define>) -> String {
+    let builtins: HashMap<&str, (Vec<DataType>, DataType)> = builtin_signatures();
+    return did_you_mean(
+        func,
+        builtins
+            .keys()
+            .copied()
+            .chain(functions.keys().map(String::as_str)),
+    );
+}
+
+// Helper function
+// Takes an identifier, the declared types, and the Location of the statement using the identifier, and checks
+// that the identifier is a declared vector
+// Function panics if it is not
+fn vector_checker(id: &String, declared: &HashMap<String, DataType>, location: &Location) {
+    match declared.get(id) {
+        Some(DataType::Vector) => {}
+        Some(data_type) => panic!(
+            "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is declared as a {} but a vector is expected.\n\n",
+            location,
+            id,
+            type_name(*data_type)
+        ),
+        None => panic!("{}", undeclared_error(id, declared, location, Some("vector"))),
+    }
+}
+
+// Helper function
+// Takes an identifier, the identifiers given a value so far, and the Location of the statement using the
+// identifier, and checks that the identifier is one of them
+// Inputs are read first, then the process ops run in the order the dataflow sorter gives them, then the output ops,
+// whatever the section order
+// Function panics if it is not
+fn assigned_checker(id: &String, assigned: &[(String, Location)], location: &Location) {
+    assert!(
+        assigned.iter().any(|(other, _)| other == id),
+        "\n\n; SEMANTIC ERROR[E0012]!\n; {}: '{}' is used before it is given a value in the data, input or process section.\n\n",
+        location,
+        id
+    );
+}
+
+// Helper function
+// Takes an identifier, the identifiers given a value so far with the Locations they were given it at, and the
+// Location of the statement giving it a value, and records that the identifier is given a value
+// Function panics if it was given one already
+fn assignment_checker(id: &String, assigned: &mut Vec<(String, Location)>, location: &Location) {
+    if let Some((_, first)) = assigned.iter().find(|(other, _)| other == id) {
+        panic!(
+            "\n\n; SEMANTIC ERROR[E0013]!\n; {}: '{}' is given a value more than once.\n; {}: note: '{}' is first given a value here\n; help: give the new value to a new data entry\n\n",
+            location, id, first, id
+        );
+    }
+    assigned.push((id.clone(), location.clone()));
+}
+
+// Helper function
+// Takes an identifier, the constants, and the Location of the statement giving the identifier a value
+// Function panics if the identifier is a constant
+fn constant_assignment_checker(id: &String, consts: &[Const], location: &Location) {
+    if let Some(constant) = consts.iter().find(|constant| constant.id == *id) {
+        panic!(
+            "\n\n; SEMANTIC ERROR[E0015]!\n; {}: '{}' is a constant and cannot be given a value.\n; {}: note: '{}' is declared as a constant here\n\n",
+            location, id, constant.location, id
+        );
+    }
+}
+
+// Helper function
+// Takes an identifier, the constants, and the Location of the statement using the identifier
+// Function panics if the identifier is a string or boolean constant, which only 'read' arguments can use
+fn constant_use_checker(id: &String, consts: &[Const], location: &Location) {
+    if let Some(constant) = consts.iter().find(|constant| constant.id == *id) {
+        assert!(
+            is_numeric(constant),
+            "\n\n; SEMANTIC ERROR[E0015]!\n; {}: Constant '{}' is {}, which can only be given as an argument to 'read'.\n\n",
+            location,
+            id,
+            constant.value
+        );
+    }
+}
+
+// Helper function
+// Takes a declared identifier and the Program, and returns the Location of the data entry declaring it
+fn declaration_finder<'a>(id: &String, program: &'a Program) -> &'a Location {
+    return program
+        .datadefs
+        .iter()
+        .find(|datadef| datadef.id == *id)
+        .map(|datadef| &datadef.location)
+        .unwrap();
+}
+
+// Semantic Checker
+// Checks user functions, and that every identifier used in the input, process, and output sections is
+// declared in the data section with a type matching the value it is given, and is given a value before it is used
+// Takes a Program
+// Returns the Signatures of the user functions that are called
+// Function panics if semantic errors are found
+pub fn semantic_checker(program: &Program) -> Signatures {
+    let mut signatures: Signatures = Signatures::new();
+    let mut declared: HashMap<String, DataType> = HashMap::new();
+    let mut assigned: Vec<(String, Location)> = Vec::new();
+    // Numeric constants are numbers that have their value from the start
+    for (i, constant) in program.consts.iter().enumerate() {
+        if let Some(first) = program.consts[..i]
+            .iter()
+            .find(|other| other.id == constant.id)
+        {
+            panic!(
+                "\n\n; SEMANTIC ERROR[E0008]!\n; {}: '{}' is declared more than once.\n; {}: note: '{}' is also declared here\n\n",
+                constant.location, constant.id, first.location, constant.id
+            );
+        }
+        if is_numeric(constant) {
+            declared.insert(constant.id.clone(), DataType::Number);
+            assigned.push((constant.id.clone(), constant.location.clone()));
+        }
+    }
+    for (i, datadef) in program.datadefs.iter().enumerate() {
+        let other: Option<&Location> = program
+            .consts
+            .iter()
+            .find(|constant| constant.id == datadef.id)
+            .map(|constant| &constant.location)
+            .or(program.datadefs[..i]
+                .iter()
+                .find(|other| other.id == datadef.id)
+                .map(|other| &other.location));
+        if let Some(other) = other {
+            panic!(
+                "\n\n; SEMANTIC ERROR[E0008]!\n; {}: '{}' is declared more than once.\n; {}: note: '{}' is also declared here\n\n",
+                datadef.location, datadef.id, other, datadef.id
+            );
+        }
+        declared.insert(datadef.id.clone(), datadef.data_type);
+    }
+
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        let location: &Location = &define.location;
+        if let Some(other) = functions.get(&define.name) {
+            panic!(
+                "\n\n; SEMANTIC ERROR[E0008]!\n; {}: Function '{}' is defined more than once.\n; {}: note: '{}' is also defined here\n\n",
+                location, define.name, other.location, define.name
+            );
+        }
+        for (i, param) in define.params.iter().enumerate() {
+            assert!(
+                !define.params[..i].contains(param),
+                "\n\n; SEMANTIC ERROR[E0008]!\n; {}: Parameter '{}' appears more than once in the definition of '{}'.\n\n",
+                location,
+                param,
+                define.name
+            );
+        }
+        define_checker(define, &functions, &define.body);
+        functions.insert(define.name.clone(), define);
+    }
+
+    // Initialisers are evaluated before the inputs are read, in the order the data entries are declared
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            let location: &Location = &datadef.location;
+            let mut ids: Vec<String> = Vec::new();
+            identifiers(init, &mut ids);
+            for id in &ids {
+                constant_use_checker(id, &program.consts, location);
+            }
+            let init_type: DataType =
+                expression_checker(init, &declared, &functions, &mut signatures, location);
+            for id in &ids {
+                assigned_checker(id, &assigned, location);
+            }
+            assert!(
+                datadef.data_type == init_type,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is declared as a {} but is initialised with a {}.\n\n",
+                location,
+                datadef.id,
+                type_name(datadef.data_type),
+                type_name(init_type)
+            );
+            assignment_checker(&datadef.id, &mut assigned, location);
+        }
+    }
+
+    for inputop in &program.inputops {
+        let location: &Location = &inputop.location;
+        constant_assignment_checker(&inputop.id, &program.consts, location);
+        let read_type: DataType = match inputop.source {
+            Source::Table { .. } => DataType::Table,
+            _ => DataType::Vector,
+        };
+        match declared.get(&inputop.id) {
+            Some(data_type) => assert!(
+                *data_type == read_type,
+                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is declared as a {} but is read as a {}.\n; {}: note: '{}' is declared as a {} here\n\n",
+                location,
+                inputop.id,
+                type_name(*data_type),
+                type_name(read_type),
+                declaration_finder(&inputop.id, program),
+                inputop.id,
+                type_name(*data_type)
+            ),
+            None => panic!("{}", undeclared_error(&inputop.id, &declared, location, Some(type_name(read_type)))
+            ),
+        }
+        assignment_checker(&inputop.id, &mut assigned, location);
+    }
+
+    for processop in &program.processops {
+        let location: &Location = &processop.location;
+        let mut ids: Vec<String> = Vec::new();
+        identifiers(&processop.expr, &mut ids);
+        for id in &ids {
+            constant_use_checker(id, &program.consts, location);
+        }
+        for id in &processop.ids {
+            constant_assignment_checker(id, &program.consts, location);
+        }
+        let expr_types: Vec<DataType> = match &processop.expr {
+            Expr::Call(func, args) if processop.ids.len() > 1 => {
+                let arg_types: Vec<DataType> = args
+                    .iter()
+                    .map(|arg| {
+                        expression_checker(arg, &declared, &functions, &mut signatures, location)
+                    })
+                    .collect();
+                match multiple_results(func, &arg_types) {
+                    Some(results) => results,
+                    None => panic!(
+                        "\n\n; SEMANTIC ERROR[E0014]!\n; {}: '{}' returns one value but is assigned to {} identifiers.\n\n",
+                        location,
+                        func,
+                        processop.ids.len()
+                    ),
+                }
+            }
+            _ if processop.ids.len() > 1 => panic!(
+                "\n\n; SEMANTIC ERROR[E0014]!\n; {}: Only a call with several results can be assigned to several identifiers.\n\n",
+                location
+            ),
+            expr => vec![expression_checker(
+                expr,
+                &declared,
+                &functions,
+                &mut signatures,
+                location,
+            )],
+        };
+        assert!(
+            expr_types.len() == processop.ids.len(),
+            "\n\n; SEMANTIC ERROR[E0014]!\n; {}: {} values are assigned to {} identifiers.\n\n",
+            location,
+            expr_types.len(),
+            processop.ids.len()
+        );
+
+        for id in &ids {
+            assigned_checker(id, &assigned, location);
+        }
+
+        for (id, expr_type) in processop.ids.iter().zip(expr_types) {
+            match declared.get(id) {
+                Some(data_type) => assert!(
+                    *data_type == expr_type,
+                    "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is declared as a {} but is assigned a {}.\n; {}: note: '{}' is declared as a {} here\n\n",
+                    location,
+                    id,
+                    type_name(*data_type),
+                    type_name(expr_type),
+                    declaration_finder(id, program),
+                    id,
+                    type_name(*data_type)
+                ),
+                None => panic!("{}", undeclared_error(id, &declared, location, Some(type_name(expr_type)))
+                ),
+            }
+            assignment_checker(id, &mut assigned, location);
+        }
+    }
+
+    for (outputop, location) in &program.outputops {
+        match outputop {
+            OutputOp::Str(_) => {}
+            OutputOp::Id(id) => {
+                constant_use_checker(id, &program.consts, location);
+                match declared.get(id) {
+                    Some(DataType::Table) => panic!(
+                        "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is a table, output its columns instead.\n\n",
+                        location, id
+                    ),
+                    Some(_) => {}
+                    None => panic!("{}", undeclared_error(id, &declared, location, None)),
+                }
+                assigned_checker(id, &assigned, location);
+            }
+            OutputOp::Print(items) | OutputOp::Write { items, .. } => {
+                for item in items {
+                    if let PrintItem::Id(id, precision) = item {
+                        constant_use_checker(id, &program.consts, location);
+                        match declared.get(id) {
+                            Some(DataType::Vector) => assert!(
+                                precision.is_none(),
+                                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is a vector, decimals can only be given for numbers.\n\n",
+                                location,
+                                id
+                            ),
+                            Some(DataType::Number) => {}
+                            Some(DataType::Table) => panic!(
+                                "\n\n; SEMANTIC ERROR[E0011]!\n; {}: '{}' is a table, output its columns instead.\n\n",
+                                location,
+                                id
+                            ),
+                            None => panic!("{}", undeclared_error(id, &declared, location, None)),
+                        }
+                        assigned_checker(id, &assigned, location);
+                    }
+                }
+            }
+            OutputOp::Plot { x, y, .. } => {
+                vector_checker(x, &declared, location);
+                vector_checker(y, &declared, location);
+                assigned_checker(x, &assigned, location);
+                assigned_checker(y, &assigned, location);
+            }
+            OutputOp::Histogram { values, .. } => {
+                vector_checker(values, &declared, location);
+                assigned_checker(values, &assigned, location);
+            }
+        }
+    }
+
+    return signatures;
+}
@@ -0,0 +1,149 @@
+// Dataflow sorter for DA programs
+// Process ops may be written in any order: each runs once the ops giving a value to the identifiers it uses have run
+// The input ops and process ops form a dependency graph, with an edge from every op to the ops whose results it
+// uses, and the process ops are sorted so that each comes after the ones it depends on, in the order they are written
+// wherever that is already one; input ops use only constants, so they depend on nothing and are read first
+// Initialisers and constants have their values before any op runs, so they are not part of the graph
+
+use crate::ast::{identifiers, output_identifiers, Location, ProcessOp, Program};
+
+// Dependencies type stores, for every process op, the process ops whose results it uses, by index, together with
+// the identifier it uses
+type Dependencies = Vec<Vec<(usize, String)>>;
+
+// Helper function
+// Takes an identifier, the Program, and the Location of the statement using the identifier
+// Identifiers that are not declared in the data section are left to the semantic checker
+// Function panics if the identifier is a data entry that no initialiser, input op or process op gives a value
+fn producer_checker(id: &String, program: &Program, location: &Location) {
+    let Some(datadef) = program.datadefs.iter().find(|datadef| datadef.id == *id) else {
+        return;
+    };
+    assert!(
+        datadef.init.is_some()
+            || program.inputops.iter().any(|inputop| inputop.id == *id)
+            || program.processops.iter().any(|processop| processop.ids.contains(id)),
+        "\n\n; SEMANTIC ERROR[E0012]!\n; {}: '{}' is used but never given a value.\n; {}: note: '{}' is declared here\n; help: give it a value with an initialiser, an input op or a process op\n\n",
+        location,
+        id,
+        datadef.location,
+        id
+    );
+}
+
+// Helper function
+// Takes the Program and returns the Dependencies of its process ops
+fn dependency_finder(program: &Program) -> Dependencies {
+    let mut dependencies: Dependencies = Vec::new();
+    for processop in &program.processops {
+        let mut ids: Vec<String> = Vec::new();
+        identifiers(&processop.expr, &mut ids);
+        dependencies.push(
+            ids.into_iter()
+                .filter_map(|id| {
+                    program
+                        .processops
+                        .iter()
+                        .position(|other| other.ids.contains(&id))
+                        .map(|j| (j, id))
+                })
+                .collect(),
+        );
+    }
+    return dependencies;
+}
+
+// Helper function
+// Takes the Program, the Dependencies of its process ops, and which of them are sorted, where the ones that are
+// not all depend on one another in cycles
+// Returns the message of the error for the cycle that starts at the first process op that is not sorted, beginning
+// with the op of the cycle written first
+fn cycle_error(program: &Program, dependencies: &Dependencies, sorted: &[bool]) -> String {
+    let mut path: Vec<(usize, String)> = Vec::new();
+    let mut i: usize = sorted.iter().position(|sorted| !sorted).unwrap();
+    while !path.iter().any(|(other, _)| *other == i) {
+        let (j, id) = dependencies[i]
+            .iter()
+            .find(|(j, _)| !sorted[*j])
+            .unwrap()
+            .clone();
+        path.push((i, id));
+        i = j;
+    }
+    let mut cycle: Vec<(usize, String)> =
+        path.split_off(path.iter().position(|(other, _)| *other == i).unwrap());
+    let first: usize = (0..cycle.len()).min_by_key(|k| cycle[*k].0).unwrap();
+    cycle.rotate_left(first);
+
+    // Each op of the cycle is named by the identifier the op before it uses
+    let names: Vec<&String> = (0..cycle.len())
+        .map(|k| &cycle[(k + cycle.len() - 1) % cycle.len()].1)
+        .collect();
+    let mut chain: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+    chain.push(names[0]);
+    let mut notes: String = String::new();
+    for (k, (i, _)) in cycle.iter().enumerate().skip(1) {
+        notes.push_str(&format!(
+            "; {}: note: '{}' is computed here\n",
+            program.processops[*i].location, names[k]
+        ));
+    }
+    let help: &str = if cycle.len() == 1 {
+        "give the new value to a new data entry"
+    } else {
+        "give one of them a value that does not depend on the others"
+    };
+    return format!(
+        "\n\n; SEMANTIC ERROR[E0024]!\n; {}: '{}' depends on itself: {}.\n{}; help: {}\n\n",
+        program.processops[cycle[0].0].location,
+        names[0],
+        chain.join(" -> "),
+        notes,
+        help
+    );
+}
+
+// Dataflow Sorter
+// Sorts the process ops of a whole program so that every process op comes after the ones whose results it uses
+// Takes the Program to sort
+// Function panics if an identifier is used but never given a value, or if process ops depend on one another in a
+// cycle
+pub fn dataflow_sorter(program: &mut Program) {
+    for processop in &program.processops {
+        let mut ids: Vec<String> = Vec::new();
+        identifiers(&processop.expr, &mut ids);
+        for id in &ids {
+            producer_checker(id, program, &processop.location);
+        }
+    }
+    for (outputop, location) in &program.outputops {
+        let mut ids: Vec<String> = Vec::new();
+        output_identifiers(outputop, &mut ids);
+        for id in &ids {
+            producer_checker(id, program, location);
+        }
+    }
+
+    // The first process op whose dependencies are all sorted is taken each time, so the written order is kept
+    // wherever it is already a valid one
+    let dependencies: Dependencies = dependency_finder(program);
+    let mut sorted: Vec<bool> = vec![false; program.processops.len()];
+    let mut order: Vec<usize> = Vec::new();
+    while order.len() < sorted.len() {
+        let ready: Option<usize> = (0..sorted.len())
+            .find(|i| !sorted[*i] && dependencies[*i].iter().all(|(j, _)| sorted[*j]));
+        match ready {
+            Some(i) => {
+                sorted[i] = true;
+                order.push(i);
+            }
+            None => panic!("{}", cycle_error(program, &dependencies, &sorted)),
+        }
+    }
+
+    let mut processops: Vec<Option<ProcessOp>> = program.processops.drain(..).map(Some).collect();
+    program.processops = order
+        .into_iter()
+        .map(|i| processops[i].take().unwrap())
+        .collect();
+}
@@ -0,0 +1,848 @@
+// Diagnostics for DA programs
+// Every phase reports an error by panicking with a message in one textual form:
+//     ; SEMANTIC ERROR[E0013]!
+//     ; main.da:14:4: 'x' is given a value more than once.
+//     ; main.da:9:4: note: 'x' is first given a value here
+//     ; help: give the new value to a new data entry
+// The first line holds the phase and the error code, the second the Location of the error (if it has one) and the
+// message, and any further lines secondary labels, help notes, and suggested fixes, which replace the token at a
+// Location with a text, e.g. '; main.da:5:26: fix: true'
+// A 'fix:' is certain to be right and is applied by 'fix', a 'suggestion:' is a guess, e.g. the closest keyword to
+// a misspelled one, and is only shown
+// This module parses such messages back into a Diagnostic, renders it the way rustc does, with the source line
+// and a caret under the error, or as one line of JSON for tools, and explains every error code with 'explain'
+
+use crate::ast::Location;
+use crate::json::{number, object, string, Json};
+use crate::linter::LINTS;
+use std::any::Any;
+use std::fs;
+use std::panic;
+
+// MessageFormat enum will be used to indicate whether diagnostics are printed for people or as JSON lines
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
+// Diagnostic struct stores one parsed error or warning
+// Errors in the program parameters have no code
+pub struct Diagnostic {
+    pub severity: &'static str,
+    pub code: Option<String>,
+    pub location: Option<Location>,
+    pub message: String,
+    pub labels: Vec<(Location, String)>,
+    pub helps: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+// Suggestion struct stores a suggested fix: the text that replaces the token at a Location (or removes it, if the
+// text is empty), and whether it is certain to be right, so that 'fix' can apply it
+pub struct Suggestion {
+    pub location: Location,
+    pub replacement: String,
+    pub applicable: bool,
+}
+
+// Explanations table stores the long description of every error code, shown by 'explain'
+const EXPLANATIONS: [(&str, &str); 25] = [
+    (
+        "E0001",
+        "A character that no token of DA starts with was found.
+
+Identifiers are made of lowercase letters and '_', numbers of digits with an optional
+fraction, and strings are written between double quotes. Anything else outside a
+string or a '#' comment is an error:
+
+    process:
+       m = mean(x) % 2      # DA has no '%' operator
+
+Remove the character, or put the text in a string or a comment.",
+    ),
+    (
+        "E0002",
+        "The parser found a token that cannot appear where it is, or the program ended
+before an item was complete.
+
+The message names the token that was found and what was expected there instead,
+e.g. a ',', a section or 'end.'. A missing ',' between two items and a missing ')'
+are the most common causes:
+
+    process:
+       m = mean(x)
+       s = stddev(x)    # the ',' after 'mean(x)' is missing
+
+Every item of a section except the last ends with ',', and a program ends with
+'end.'. Statements belong in their own section: 'read' only in 'input:', and
+'print', 'write', 'append', 'plot' and 'histogram' only in 'output:'.",
+    ),
+    (
+        "E0003",
+        "An identifier is used that is not declared in the data section.
+
+Every identifier that is read, computed or printed must be declared with its type
+first:
+
+    data:
+       x : vector
+    process:
+       m = mean(x)      # 'm' is not declared
+
+Add 'm : number' to the data section. Inside a user function only its parameters
+can be used, so a data entry has to be passed to it as an argument.",
+    ),
+    (
+        "E0004",
+        "A section header appears twice in the same file.
+
+Sections may come in any order, but each of 'data:', 'define:', 'input:',
+'process:' and 'output:' at most once per file. Move the items of the second
+section into the first one.",
+    ),
+    (
+        "E0005",
+        "A 'read' call has a named argument that is misspelled, given twice, or invalid.
+
+The only named argument of 'read' is 'delimiter', and its value is a string of one
+character:
+
+    x = read(\"data.csv\", true, \"height\", delimiter=\";\")
+
+Any other name, a second 'delimiter=', or a delimiter such as \";;\" is an error.",
+    ),
+    (
+        "E0006",
+        "A CSV column is picked by name from a file that is read without a header row.
+
+Column names come from the header row, so reading a column by name needs 'true' as
+the second argument of 'read':
+
+    x = read(\"data.csv\", false, \"height\")    # no header to find 'height' in
+
+Pass 'true', or pick the column by its index, counted from 0.",
+    ),
+    (
+        "E0007",
+        "An included file ends with 'end.'.
+
+Files given to 'include' hold sections only; 'end.' ends the main program. Remove
+'end.' from the included file.",
+    ),
+    (
+        "E0008",
+        "The same name is declared twice: two data entries or constants, two user
+functions, or two parameters of one user function.
+
+    data:
+       x : vector,
+       x : number       # 'x' is declared already
+
+The note points at the other declaration. Rename or remove one of the two; data
+entries and constants share one set of names, also across included files.",
+    ),
+    (
+        "E0009",
+        "A function is called that is neither a statistics function nor defined in the
+define section.
+
+    define:
+       cv(v) = stddev(v) / mean(v)
+    process:
+       c = coefficient(x)    # no function 'coefficient'
+
+Call one of the statistics functions or define the function first. A user function
+can only call statistics functions and the user functions defined before it.",
+    ),
+    (
+        "E0010",
+        "A function is called with the wrong number of arguments.
+
+    m = mean(x, y)      # 'mean' takes one vector
+
+Statistics functions and user functions take exactly as many arguments as they
+have parameters.",
+    ),
+    (
+        "E0011",
+        "A value of one type is used where another type is expected.
+
+DA has three types: 'number', 'vector' and 'table'. A data entry is given values
+of its declared type only, arguments must match the parameters of the function
+they are given to, and tables are used through their columns:
+
+    data:
+       m : number
+    process:
+       m = abs(x)       # 'abs' returns a vector
+
+Declare the data entry with the type of the value, or pick a column of a table
+with 't.name' or 't[1]'. The note points at the declaration.",
+    ),
+    (
+        "E0012",
+        "A data entry is used before it is given a value, or is never given one.
+
+Initialisers are evaluated first, in declaration order, then the inputs are read,
+then the process ops run, each after the ones computing the values it uses, and
+the output ops last. An initialiser can only use constants and the entries
+initialised before it, and an entry that nothing gives a value cannot be used:
+
+    data:
+       x : vector,
+       m : number
+    process:
+       m = mean(x)      # nothing gives 'x' a value
+
+Give the entry a value in the data, input or process section. The note points at
+the declaration.",
+    ),
+    (
+        "E0013",
+        "A data entry is given a value more than once.
+
+Every data entry is given one value: by its initialiser, by an input op or by a
+process op. The note points at the first one:
+
+    input:
+       x = read(\"a.csv\", false, 0)
+    process:
+       x = abs(x)       # 'x' has its value already
+
+Give the new value to a new data entry, e.g. 'ax = abs(x)'.",
+    ),
+    (
+        "E0014",
+        "A call with several results is not assigned to as many identifiers as it
+returns values, or a call with one result is assigned to several.
+
+'regression(x, y)' returns the intercept, the slope and r squared, and 'regression'
+of a table and a vector the intercept and one slope per column:
+
+    a, b, rsq = regression(x, y)
+
+Such a call has to be the whole right-hand side of its process op.",
+    ),
+    (
+        "E0015",
+        "A constant is given a value, or a string or boolean constant is used in an
+expression.
+
+Constants have their value from where they are declared. Numeric constants can be
+used like numbers, but string and boolean constants can only be given as arguments
+to 'read':
+
+    data:
+       const file = \"data.csv\"
+    input:
+       x = read(file, true, \"height\")
+
+Declare a data entry instead if the value is computed.",
+    ),
+    (
+        "E0016",
+        "A user function is called with arguments of different types.
+
+DA functions are monomorphic: every call of a user function passes the same
+types, so that the Scheme and Prolog translations have one definition per
+function. Define a second function for the other types.",
+    ),
+    (
+        "E0017",
+        "An included file was not found.
+
+'include \"file.da\"' looks next to the including file first, then in every
+directory given with '-I', in order. Check the file name or add its directory
+with '-I dir'.",
+    ),
+    (
+        "E0018",
+        "A file includes itself, directly or through other files.
+
+The message lists the chain of includes. Move the shared sections into a file that
+the others include.",
+    ),
+    (
+        "E0019",
+        "A source file could not be opened, read or written.
+
+Check that the file exists and that its permissions allow reading it (and writing
+it for 'fmt').",
+    ),
+    (
+        "E0020",
+        "A data file read by the program does not hold the data the program expects.
+
+The file could not be read, a field is not a number, a column or JSON field does
+not exist, or a line has a different number of fields than the header. The
+message names the file and the line or element. Fix the data or the arguments of
+the input op.",
+    ),
+    (
+        "E0021",
+        "A statistic is not defined for the values it was given.
+
+The mean needs at least one value, the standard deviation at least two, a
+histogram at least one, and a multiple regression at least as many rows as
+columns plus one, with columns that are not linearly dependent. Check the data
+and any 'filter' or 'slice' in front of the call.",
+    ),
+    (
+        "E0022",
+        "Two vectors that are combined or paired have different lengths, or a slice is
+out of range.
+
+Element-wise operators, 'correlation', 'regression' and 'plot' need vectors of
+the same length, and 'slice(x, a, b)' needs 0 <= a <= b <= the length of x.",
+    ),
+    (
+        "E0023",
+        "An output file could not be written.
+
+'write', 'append', 'plot' and 'histogram' create or extend the file they are
+given. Check that its directory exists and can be written to.",
+    ),
+    (
+        "E0024",
+        "Process ops depend on their own results, directly or through other process
+ops.
+
+Process ops may be written in any order, and each runs after the ones computing
+the values it uses, so those values cannot go round in a circle:
+
+    process:
+       a = b + 1,
+       b = a * 2        # 'a' uses 'b', which uses 'a'
+
+The message lists the chain and the notes point at the other process ops in it.
+Give one of them a value that does not depend on the others.",
+    ),
+    (
+        "E0025",
+        "A constant is given as an argument to 'read' in an input section that comes
+before the data section declaring it.
+
+Sections may come in any order, but 'read' arguments are resolved when the input
+section is parsed, so the constants they use have to be declared above it:
+
+    input:
+       x = read(file, true, 0)      # 'file' is not known yet
+    data:
+       const file = \"data.csv\",
+       x : vector
+
+Move the data section before the input section. 'fix' does this by putting the
+sections in the order data, define, input, process, output.",
+    ),
+];
+
+// Helper function
+// Takes a line of a message and splits the 'file:line:column: ' Location it starts with off the rest of it
+// Returns a tuple with the Location, if the line starts with one, and the rest of the line
+pub fn location_splitter(message: &str) -> (Option<Location>, String) {
+    for (i, _) in message.match_indices(": ") {
+        let mut parts = message[..i].rsplitn(3, ':');
+        let column: Option<usize> = parts.next().and_then(|column| column.parse().ok());
+        let line: Option<usize> = parts.next().and_then(|line| line.parse().ok());
+        if let (Some(column), Some(line), Some(file)) = (column, line, parts.next()) {
+            let location: Location = Location {
+                file: String::from(file),
+                line,
+                column,
+            };
+            return (Some(location), String::from(&message[i + 2..]));
+        }
+    }
+    return (None, String::from(message));
+}
+
+// Helper function
+// Takes the payload of a panic and returns its message
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    return match payload.downcast_ref::<String>() {
+        Some(message) => message.clone(),
+        None => String::from(*payload.downcast_ref::<&str>().unwrap_or(&"Unknown error.")),
+    };
+}
+
+// Helper function
+// Takes two words and returns the number of characters that must be inserted, deleted, replaced, or swapped with
+// the next one to turn the first into the second
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between the first i characters of a and the first j characters of b
+    let mut distances: Vec<Vec<usize>> = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost: usize = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance: usize = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    return distances[a.len()][b.len()];
+}
+
+// Closest Match
+// Takes a word that is not known where it is used and the words that could have been meant there
+// Returns the one closest to it, ignoring case, if it is close enough to be a typo: a third of the word may be
+// wrong, but words of one or two letters only match if they differ in case
+// Of words equally close the first in alphabetical order is returned, so that the suggestion does not change
+// from run to run
+pub fn closest_match<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let word: String = word.to_ascii_lowercase();
+    let limit: usize = if word.len() < 3 { 0 } else { word.len() / 3 };
+    return candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate);
+}
+
+// Helper function
+// Takes a word that is not known where it is used and the words that could have been meant there
+// Returns a '; help: did you mean ...?' line for an error message if one of them is close to it, or else nothing
+pub fn did_you_mean<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    return match closest_match(word, candidates) {
+        Some(candidate) => format!("; help: did you mean '{}'?\n", candidate),
+        None => String::new(),
+    };
+}
+
+// Diagnostic Parser
+// Takes the message of a panic
+// Returns the Diagnostic it holds, or None if it is not a message of the form above (e.g. a bug in the compiler)
+pub fn diagnostic_parser(message: &str) -> Option<Diagnostic> {
+    let lines: Vec<&str> = message
+        .lines()
+        .filter_map(|line| line.strip_prefix("; "))
+        .collect();
+    let mut rest: &[&str] = &lines;
+
+    // '; SEMANTIC ERROR[E0013]!' or '; LINT WARNING[W001]!', where the code is optional
+    let mut severity: &'static str = "error";
+    let mut code: Option<String> = None;
+    if let Some((first, words)) = lines
+        .first()
+        .and_then(|line| line.strip_suffix('!'))
+        .and_then(|line| line.split_once(' '))
+    {
+        let (kind, bracketed) = match words.split_once('[') {
+            Some((kind, bracketed)) => (kind, bracketed.strip_suffix(']')),
+            None => (words, None),
+        };
+        if (kind == "ERROR" || kind == "WARNING") && first.chars().all(|c| c.is_ascii_uppercase()) {
+            if kind == "WARNING" {
+                severity = "warning";
+            }
+            code = bracketed.map(String::from);
+            rest = &lines[1..];
+        }
+    }
+
+    let (location, mut message) = location_splitter(rest.first()?);
+    let mut labels: Vec<(Location, String)> = Vec::new();
+    let mut helps: Vec<String> = Vec::new();
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for line in &rest[1..] {
+        if let Some(help) = line.strip_prefix("help: ") {
+            helps.push(String::from(help));
+            continue;
+        }
+        match location_splitter(line) {
+            (Some(location), text) if text.starts_with("note: ") => {
+                labels.push((location, String::from(&text["note: ".len()..])));
+            }
+            (Some(location), text)
+                if text.starts_with("suggestion: ") || text.starts_with("fix: ") =>
+            {
+                let (kind, replacement) = text.split_once(": ").unwrap();
+                suggestions.push(Suggestion {
+                    location,
+                    replacement: String::from(replacement),
+                    applicable: kind == "fix",
+                });
+            }
+            _ => {
+                message.push(' ');
+                message.push_str(line);
+            }
+        }
+    }
+    return Some(Diagnostic {
+        severity,
+        code,
+        location,
+        message,
+        labels,
+        helps,
+        suggestions,
+    });
+}
+
+// Helper function
+// Takes a line of source and a column in it, counted from 1
+// Returns how many characters the token starting at the column spans: a string up to its closing quote, a run of
+// letters, digits, '_' and inner '.', a two-character comparison, or else one character
+pub fn token_width(line: &str, column: usize) -> usize {
+    let chars: Vec<char> = line.chars().skip(column.saturating_sub(1)).collect();
+    match chars.first() {
+        None => return 1,
+        Some('"') => {
+            return match chars[1..].iter().position(|c| *c == '"') {
+                Some(end) => end + 2,
+                None => chars.len(),
+            }
+        }
+        Some(c) if c.is_ascii_alphanumeric() || *c == '_' => {
+            // A '.' is part of a number or a column ('t.revenue'), but not the one of 'end.'
+            let is_part = |i: usize| {
+                chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '_'
+                    || (chars[i] == '.'
+                        && chars.get(i + 1).is_some_and(|c| c.is_ascii_alphanumeric()))
+            };
+            return (0..chars.len()).take_while(|i| is_part(*i)).count();
+        }
+        Some('<' | '>' | '=' | '!') if chars.get(1) == Some(&'=') => return 2,
+        _ => return 1,
+    }
+}
+
+// Helper function
+// Takes a Location, the source line it points into, and the message about it
+// Returns the Location of the first identifier the message quotes, e.g. 'y' in "Identifier 'y' is not declared",
+// where it appears on the line at or after the Location outside strings, or else the Location itself
+// The checker locates errors at the statement they are found in, so this puts the caret under the name at fault
+fn focus_finder(location: &Location, line: &str, message: &str) -> Location {
+    let is_id_char = |c: char| c.is_ascii_lowercase() || c == '_';
+    let name: Option<&str> = message
+        .split('\'')
+        .nth(1)
+        .filter(|name| !name.is_empty() && name.chars().all(is_id_char));
+    let mut focused: Location = location.clone();
+    let Some(name) = name else {
+        return focused;
+    };
+    let chars: Vec<char> = line.chars().collect();
+    let name_chars: Vec<char> = name.chars().collect();
+    let mut in_string: bool = false;
+    for i in location.column.saturating_sub(1)..chars.len() {
+        if chars[i] == '"' {
+            in_string = !in_string;
+        }
+        if in_string || !chars[i..].starts_with(&name_chars) {
+            continue;
+        }
+        let before: bool = i > 0 && is_id_char(chars[i - 1]);
+        let after: bool = chars
+            .get(i + name_chars.len())
+            .is_some_and(|c| is_id_char(*c));
+        if !before && !after {
+            focused.column = i + 1;
+            return focused;
+        }
+    }
+    return focused;
+}
+
+// Helper function
+// Takes the width of the line number gutter, a Location, the source line it points into, the character to
+// underline it with, and the text of the label
+// Returns the lines of the snippet: the numbered source line and the underline with the label after it
+fn snippet_renderer(
+    gutter: usize,
+    location: &Location,
+    line: &str,
+    underline: char,
+    label: &str,
+) -> String {
+    let width: usize = token_width(line, location.column);
+    let mut marker: String = format!(
+        "{}{}",
+        " ".repeat(location.column.saturating_sub(1)),
+        underline.to_string().repeat(width)
+    );
+    if !label.is_empty() {
+        marker.push(' ');
+        marker.push_str(label);
+    }
+    return format!(
+        "{:>gutter$} | {}\n{:>gutter$} | {}\n",
+        location.line,
+        line.trim_end(),
+        "",
+        marker
+    );
+}
+
+// Helper function
+// Takes a Location and a function that returns the contents of a source file
+// Returns the line the Location points into, if it is available
+fn source_line(location: &Location, source: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    return source(&location.file)?
+        .lines()
+        .nth(location.line.checked_sub(1)?)
+        .map(String::from);
+}
+
+// Helper function
+// Takes a Diagnostic, one of its suggestions, and a function that returns the contents of a source file
+// Returns the suggestion as a help note: a suggestion for a word at fault asks whether it was meant, unless it only
+// adds to it (e.g. 'end.'), an empty one removes its token, and any other replaces its token
+pub fn suggestion_help(
+    diagnostic: &Diagnostic,
+    suggestion: &Suggestion,
+    source: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    let (location, replacement): (&Location, &str) =
+        (&suggestion.location, &suggestion.replacement);
+    let line: String = match source_line(location, source) {
+        Some(line) => line,
+        None => return format!("replace the token at {location} with '{replacement}'"),
+    };
+    let original: String = line
+        .chars()
+        .skip(location.column.saturating_sub(1))
+        .take(token_width(&line, location.column))
+        .collect();
+    if replacement.is_empty() {
+        return format!("remove '{original}'");
+    } else if diagnostic.location.as_ref() == Some(location)
+        && original.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !replacement.starts_with(&original)
+    {
+        return format!("did you mean '{replacement}'?");
+    }
+    return format!("replace '{original}' with '{replacement}'");
+}
+
+// Diagnostic Renderer
+// Takes a Diagnostic and a function that returns the contents of a source file, or None if it is not available
+// Returns the diagnostic rendered like rustc renders its errors, ending with a newline
+//     error[E0013]: 'x' is given a value more than once.
+//       --> main.da:14:4
+//        |
+//     14 |    x = abs(x)
+//        |    ^
+//        |
+//      9 |    x = read("a.csv", false, 0)
+//        |    - 'x' is first given a value here
+//        |
+//        = help: remove one of the two, or give the second value to a new data entry
+// Lines that are not available are left out, and so is the snippet of a label in another file than the error,
+// which is given with its Location instead
+pub fn diagnostic_renderer(
+    diagnostic: &Diagnostic,
+    source: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    let source_line = |location: &Location| source_line(location, source);
+    let mut output: String = String::from(diagnostic.severity);
+    if let Some(code) = &diagnostic.code {
+        output.push_str(&format!("[{code}]"));
+    }
+    output.push_str(&format!(": {}\n", diagnostic.message));
+
+    let mut lines: Vec<usize> = diagnostic
+        .labels
+        .iter()
+        .map(|(location, _)| location.line)
+        .collect();
+    if let Some(location) = &diagnostic.location {
+        lines.push(location.line);
+    }
+    let gutter: usize = lines.iter().max().map_or(1, |line| line.to_string().len());
+    let blank: String = format!("{} |\n", " ".repeat(gutter));
+
+    if let Some(location) = &diagnostic.location {
+        match source_line(location) {
+            Some(line) => {
+                let location: Location = focus_finder(location, &line, &diagnostic.message);
+                output.push_str(&format!("{}--> {}\n", " ".repeat(gutter), location));
+                output.push_str(&blank);
+                output.push_str(&snippet_renderer(gutter, &location, &line, '^', ""));
+            }
+            None => output.push_str(&format!("{}--> {}\n", " ".repeat(gutter), location)),
+        }
+    }
+    for (location, label) in &diagnostic.labels {
+        let same_file: bool = diagnostic
+            .location
+            .as_ref()
+            .is_some_and(|primary| primary.file == location.file);
+        match source_line(location) {
+            Some(line) if same_file => {
+                let location: Location = focus_finder(location, &line, label);
+                output.push_str(&blank);
+                output.push_str(&snippet_renderer(gutter, &location, &line, '-', label));
+            }
+            _ => output.push_str(&format!(
+                "{} = note: {}: {}\n",
+                " ".repeat(gutter),
+                location,
+                label
+            )),
+        }
+    }
+    let mut helps: Vec<String> = Vec::new();
+    for suggestion in &diagnostic.suggestions {
+        helps.push(suggestion_help(diagnostic, suggestion, source));
+    }
+    helps.extend(diagnostic.helps.iter().cloned());
+    if !helps.is_empty() {
+        output.push_str(&blank);
+    }
+    for help in &helps {
+        output.push_str(&format!("{} = help: {}\n", " ".repeat(gutter), help));
+    }
+    return output;
+}
+
+// Helper function
+// Takes a Location and the source line it points into, if it is available
+// Returns the JSON members of the span of the token at the Location: the file, line and column where it starts, and
+// the line and column just after it
+fn span_members(location: &Location, line: Option<&str>) -> Vec<(&'static str, Json)> {
+    let width: usize = line.map_or(1, |line| token_width(line, location.column));
+    return vec![
+        ("file", string(&location.file)),
+        ("line", number(location.line)),
+        ("column", number(location.column)),
+        ("end_line", number(location.line)),
+        ("end_column", number(location.column + width)),
+    ];
+}
+
+// Diagnostic JSON
+// Takes a Diagnostic and a function that returns the contents of a source file, or None if it is not available
+// Returns the diagnostic as a JSON object: its severity, code and message, the span of the error ('file', 'line'
+// and 'column' where it starts and 'end_line' and 'end_column' just after it, all null without a Location), its
+// labels and suggested fixes with their spans, its help notes, and the human rendering as 'rendered'
+pub fn diagnostic_json(diagnostic: &Diagnostic, source: &dyn Fn(&str) -> Option<String>) -> Json {
+    let mut members: Vec<(&str, Json)> = vec![
+        ("severity", string(diagnostic.severity)),
+        (
+            "code",
+            diagnostic.code.as_deref().map_or(Json::Null, string),
+        ),
+        ("message", string(&diagnostic.message)),
+    ];
+    match &diagnostic.location {
+        Some(location) => {
+            let line: Option<String> = source_line(location, source);
+            let location: Location = match &line {
+                Some(line) => focus_finder(location, line, &diagnostic.message),
+                None => location.clone(),
+            };
+            members.extend(span_members(&location, line.as_deref()));
+        }
+        None => {
+            for name in ["file", "line", "column", "end_line", "end_column"] {
+                members.push((name, Json::Null));
+            }
+        }
+    }
+    let labels: Vec<Json> = diagnostic
+        .labels
+        .iter()
+        .map(|(location, label)| {
+            let line: Option<String> = source_line(location, source);
+            let location: Location = match &line {
+                Some(line) => focus_finder(location, line, label),
+                None => location.clone(),
+            };
+            let mut members = span_members(&location, line.as_deref());
+            members.push(("message", string(label)));
+            object(members)
+        })
+        .collect();
+    let suggestions: Vec<Json> = diagnostic
+        .suggestions
+        .iter()
+        .map(|suggestion| {
+            let line: Option<String> = source_line(&suggestion.location, source);
+            let mut members = span_members(&suggestion.location, line.as_deref());
+            members.push(("replacement", string(&suggestion.replacement)));
+            members.push(("applicable", Json::Bool(suggestion.applicable)));
+            object(members)
+        })
+        .collect();
+    members.push(("labels", Json::Array(labels)));
+    members.push((
+        "helps",
+        Json::Array(diagnostic.helps.iter().map(|help| string(help)).collect()),
+    ));
+    members.push(("suggestions", Json::Array(suggestions)));
+    members.push(("rendered", string(&diagnostic_renderer(diagnostic, source))));
+    return object(members);
+}
+
+// Diagnostic Reporter
+// Prints a Diagnostic in the given format: errors rendered for people go to stderr and warnings to stdout, and
+// JSON diagnostics go to stdout, one per line
+pub fn diagnostic_reporter(diagnostic: &Diagnostic, format: MessageFormat) {
+    match format {
+        MessageFormat::Json => println!("{}", diagnostic_json(diagnostic, &file_source)),
+        MessageFormat::Human if diagnostic.severity == "warning" => {
+            println!("{}", diagnostic_renderer(diagnostic, &file_source))
+        }
+        MessageFormat::Human => eprint!("\n{}", diagnostic_renderer(diagnostic, &file_source)),
+    }
+}
+
+// Helper function
+// Takes the name of a source file and returns its contents, if it can be read
+pub fn file_source(file: &str) -> Option<String> {
+    return fs::read_to_string(file).ok();
+}
+
+// Hook Installer
+// Makes panics that carry a diagnostic report it in the given format instead of printing the panic message
+// Other panics are bugs and are still reported by the default hook
+pub fn hook_installer(format: MessageFormat) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        match diagnostic_parser(&panic_message(info.payload())) {
+            Some(diagnostic) => diagnostic_reporter(&diagnostic, format),
+            None => default_hook(info),
+        }
+    }));
+}
+
+// Explanation Generator
+// Takes an error code, or the code or name of a lint
+// Returns the long description of the error, or the description of the lint, ending with a newline
+// Function panics if there is no such code
+pub fn explanation_generator(code: &str) -> String {
+    let code: String = code.to_ascii_uppercase();
+    if let Some((_, text)) = EXPLANATIONS.iter().find(|(other, _)| *other == code) {
+        return format!("{code}: {text}\n");
+    }
+    match LINTS
+        .iter()
+        .find(|(other, name, _)| *other == code || name.eq_ignore_ascii_case(&code))
+    {
+        Some((code, name, description)) => {
+            return format!(
+                "{code} ({name}): warns about {description}.\n\nIt can be allowed with '-A {code}' or denied with '-D {code}'.\n"
+            )
+        }
+        None => panic!("\n\n; Unknown error code '{}'!\n\n", code),
+    }
+}
@@ -0,0 +1,264 @@
+// Dumps of the lexer and parser output, for debugging the grammar and for external tools
+// '--emit tokens' prints one Token per line and '--emit ast' prints the parsed Program as JSON
+// STRING lexemes are dumped as they were written, quotes and escapes included, like the Program stores them
+
+use crate::ast::{Column, DataType, Expr, Location, OutputOp, PrintItem, Program, Source};
+use crate::json::{number, object, string, Json};
+use crate::{token_end, Token};
+
+// Tokens Dumper
+// Takes the Tokens of a file, comments included
+// Returns one line per Token with its span ('file:line:column-line:column'), its type and its lexeme, where a
+// newline inside a lexeme is shown as '\n'
+pub fn tokens_dumper(tokens: &[Token]) -> String {
+    let mut output: String = String::new();
+    for token in tokens {
+        let (end_line, end_column) = token_end(token);
+        output.push_str(&format!(
+            "{}-{}:{} {:?} {}\n",
+            token.location,
+            end_line,
+            end_column,
+            token.token,
+            token.lexeme.replace('\n', "\\n")
+        ));
+    }
+    return output;
+}
+
+// Helper function
+// Takes a Location and returns it as a JSON object with its file, line and column
+fn location_json(location: &Location) -> Json {
+    return object(vec![
+        ("file", string(&location.file)),
+        ("line", number(location.line)),
+        ("column", number(location.column)),
+    ]);
+}
+
+// Helper function
+// Takes a text or nothing and returns it as a JSON string or null
+fn optional_json(text: &Option<String>) -> Json {
+    return match text {
+        Some(text) => string(text),
+        None => Json::Null,
+    };
+}
+
+// Helper function
+// Takes a list of texts and returns them as a JSON array of strings
+fn strings_json(texts: &[String]) -> Json {
+    return Json::Array(texts.iter().map(|text| string(text)).collect());
+}
+
+// Helper function
+// Takes an expression and returns it as a JSON object whose "kind" is its Expr variant in lowercase
+fn expr_json(expr: &Expr) -> Json {
+    return match expr {
+        Expr::Num(value) => object(vec![("kind", string("num")), ("value", string(value))]),
+        Expr::Id(id) => object(vec![("kind", string("id")), ("id", string(id))]),
+        Expr::Call(func, args) => object(vec![
+            ("kind", string("call")),
+            ("function", string(func)),
+            ("args", Json::Array(args.iter().map(expr_json).collect())),
+        ]),
+        Expr::Binary(operator, lhs, rhs) => object(vec![
+            ("kind", string("binary")),
+            ("operator", string(&operator.to_string())),
+            ("lhs", expr_json(lhs)),
+            ("rhs", expr_json(rhs)),
+        ]),
+        Expr::Negate(operand) => object(vec![
+            ("kind", string("negate")),
+            ("operand", expr_json(operand)),
+        ]),
+        Expr::Filter(vector, comparison, threshold) => object(vec![
+            ("kind", string("filter")),
+            ("vector", expr_json(vector)),
+            ("comparison", string(comparison)),
+            ("threshold", expr_json(threshold)),
+        ]),
+        Expr::Column(table, column) => object(vec![
+            ("kind", string("column")),
+            ("table", expr_json(table)),
+            match column {
+                Column::Name(name) => ("name", string(name)),
+                Column::Index(index) => ("index", number(*index)),
+            },
+        ]),
+    };
+}
+
+// Helper function
+// Takes the source of an input op and returns it as a JSON object whose "kind" is its loader
+fn source_json(source: &Source) -> Json {
+    return match source {
+        Source::Csv {
+            file,
+            header,
+            column,
+            delimiter,
+        } => object(vec![
+            ("kind", string("read")),
+            ("file", string(file)),
+            ("header", Json::Bool(*header)),
+            (
+                "column",
+                match column {
+                    Column::Name(_) => string(&column.to_string()),
+                    Column::Index(index) => number(*index),
+                },
+            ),
+            ("delimiter", optional_json(delimiter)),
+        ]),
+        Source::Json { file, field } => object(vec![
+            ("kind", string("read_json")),
+            ("file", string(file)),
+            ("field", optional_json(field)),
+        ]),
+        Source::Lines { file } => {
+            object(vec![("kind", string("read_lines")), ("file", string(file))])
+        }
+        Source::Table { file, header } => object(vec![
+            ("kind", string("read_table")),
+            ("file", string(file)),
+            ("header", Json::Bool(*header)),
+        ]),
+    };
+}
+
+// Helper function
+// Takes the items of a print, write or append statement and returns them as a JSON array
+// An identifier without decimals has a null "decimals"
+fn items_json(items: &[PrintItem]) -> Json {
+    return Json::Array(
+        items
+            .iter()
+            .map(|item| match item {
+                PrintItem::Str(text) => {
+                    object(vec![("kind", string("str")), ("value", string(text))])
+                }
+                PrintItem::Id(id, decimals) => object(vec![
+                    ("kind", string("id")),
+                    ("id", string(id)),
+                    ("decimals", decimals.map_or(Json::Null, number)),
+                ]),
+            })
+            .collect(),
+    );
+}
+
+// Helper function
+// Takes an output op and its Location, and returns them as a JSON object whose "kind" is its statement
+fn outputop_json(outputop: &OutputOp, location: &Location) -> Json {
+    let mut members: Vec<(&str, Json)> = match outputop {
+        OutputOp::Str(text) => vec![("kind", string("str")), ("value", string(text))],
+        OutputOp::Id(id) => vec![("kind", string("id")), ("id", string(id))],
+        OutputOp::Print(items) => vec![("kind", string("print")), ("items", items_json(items))],
+        OutputOp::Write {
+            file,
+            items,
+            append,
+        } => vec![
+            ("kind", string(if *append { "append" } else { "write" })),
+            ("file", string(file)),
+            ("items", items_json(items)),
+        ],
+        OutputOp::Plot { x, y, file } => vec![
+            ("kind", string("plot")),
+            ("x", string(x)),
+            ("y", string(y)),
+            ("file", string(file)),
+        ],
+        OutputOp::Histogram { values, file } => vec![
+            ("kind", string("histogram")),
+            ("values", string(values)),
+            ("file", string(file)),
+        ],
+    };
+    members.push(("location", location_json(location)));
+    return object(members);
+}
+
+// Program Dumper
+// Takes a parsed Program, included files spliced in
+// Returns it as indented JSON text with one member per section, each entry carrying its Location
+pub fn program_dumper(program: &Program) -> String {
+    let consts: Vec<Json> = program
+        .consts
+        .iter()
+        .map(|constant| {
+            object(vec![
+                ("id", string(&constant.id)),
+                ("value", string(&constant.value)),
+                ("location", location_json(&constant.location)),
+            ])
+        })
+        .collect();
+    let datadefs: Vec<Json> = program
+        .datadefs
+        .iter()
+        .map(|datadef| {
+            let data_type: &str = match datadef.data_type {
+                DataType::Vector => "vector",
+                DataType::Number => "number",
+                DataType::Table => "table",
+            };
+            object(vec![
+                ("id", string(&datadef.id)),
+                ("type", string(data_type)),
+                ("init", datadef.init.as_ref().map_or(Json::Null, expr_json)),
+                ("location", location_json(&datadef.location)),
+            ])
+        })
+        .collect();
+    let defines: Vec<Json> = program
+        .defines
+        .iter()
+        .map(|define| {
+            object(vec![
+                ("name", string(&define.name)),
+                ("params", strings_json(&define.params)),
+                ("body", expr_json(&define.body)),
+                ("location", location_json(&define.location)),
+            ])
+        })
+        .collect();
+    let inputops: Vec<Json> = program
+        .inputops
+        .iter()
+        .map(|inputop| {
+            object(vec![
+                ("id", string(&inputop.id)),
+                ("source", source_json(&inputop.source)),
+                ("location", location_json(&inputop.location)),
+            ])
+        })
+        .collect();
+    let processops: Vec<Json> = program
+        .processops
+        .iter()
+        .map(|processop| {
+            object(vec![
+                ("ids", strings_json(&processop.ids)),
+                ("expr", expr_json(&processop.expr)),
+                ("location", location_json(&processop.location)),
+            ])
+        })
+        .collect();
+    let outputops: Vec<Json> = program
+        .outputops
+        .iter()
+        .map(|(outputop, location)| outputop_json(outputop, location))
+        .collect();
+
+    return object(vec![
+        ("consts", Json::Array(consts)),
+        ("data", Json::Array(datadefs)),
+        ("define", Json::Array(defines)),
+        ("input", Json::Array(inputops)),
+        ("process", Json::Array(processops)),
+        ("output", Json::Array(outputops)),
+    ])
+    .pretty();
+}
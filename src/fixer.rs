@@ -0,0 +1,208 @@
+// Fixer for DA programs
+// 'fix file' applies the fixes the diagnostics suggest that are certain to be right: lowercasing a keyword or an
+// identifier written with capitals, a missing ',' between two items, a ',' after the last item, the '.' of 'end.',
+// 'true' for the header of a read by column name, and ':' in a data entry declared the 2023 way with '=', since the
+// file is checked in the current edition
+// Errors are reported by panicking and only the first one is found, so the file is checked, fixed and checked again
+// until it has no errors or its first error has no fix
+// Sections may appear in any order, unless a constant is given to 'read' above the data section declaring it, and
+// then the sections are put in the order data, define, input, process, output
+// Guesses such as the closest keyword to a misspelled one are left to the programmer
+
+use crate::checker::semantic_checker;
+use crate::dataflow::dataflow_sorter;
+use crate::diagnostics::{diagnostic_parser, panic_message, token_width, Diagnostic, Suggestion};
+use crate::{comment_stripper, lexer, source_parser, Edition, Includes, Token, TokenTypes};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+// Helper function
+// Lexes, parses and (for a whole program) checks the text of a file
+// Takes the text, the name of the file, and the search path
+// Returns the message of the first error found, or None if there is none
+fn error_finder(text: &str, file: &str, search_path: &[PathBuf]) -> Option<String> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let tokens: Vec<Token> = comment_stripper(lexer(String::from(text), file));
+        let mut includes: Includes = Includes {
+            search_path: search_path.to_vec(),
+            stack: vec![PathBuf::from(file)],
+            included: Vec::new(),
+            // Fixes bring a program up to the current edition
+            edition: Edition::Edition2024,
+            warnings: Vec::new(),
+        };
+        let (mut program, whole) = source_parser(tokens, file, &mut includes);
+        if whole {
+            dataflow_sorter(&mut program);
+            semantic_checker(&program);
+        }
+    }));
+    return result.err().map(|payload| panic_message(&*payload));
+}
+
+// Helper function
+// Takes the text of a file and suggestions for it
+// Returns the text with every suggestion applied, from the last to the first so that applying one does not move the
+// ones before it
+fn suggestions_applier(text: &str, suggestions: &[&Suggestion]) -> String {
+    let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
+    let mut suggestions: Vec<&Suggestion> = suggestions.to_vec();
+    suggestions.sort_by_key(|suggestion| (suggestion.location.line, suggestion.location.column));
+    for suggestion in suggestions.iter().rev() {
+        let column: usize = suggestion.location.column;
+        let line: &mut String = match lines.get_mut(suggestion.location.line.wrapping_sub(1)) {
+            Some(line) => line,
+            None => continue,
+        };
+        let chars: Vec<char> = line.chars().collect();
+        if column == 0 || column > chars.len() {
+            continue;
+        }
+        let end: usize = column - 1 + token_width(line, column);
+        let mut fixed: String = chars[..column - 1].iter().collect();
+        fixed.push_str(&suggestion.replacement);
+        fixed.extend(&chars[end..]);
+        *line = fixed;
+    }
+    return lines.join("\n");
+}
+
+// Helper function
+// Takes a TokenTypes and returns the place of the section it starts in the order data, define, input, process, output
+fn section_rank(token: TokenTypes) -> Option<usize> {
+    return match token {
+        TokenTypes::DATA => Some(0),
+        TokenTypes::DEFINE => Some(1),
+        TokenTypes::INPUT => Some(2),
+        TokenTypes::PROCESS => Some(3),
+        TokenTypes::OUTPUT => Some(4),
+        _ => None,
+    };
+}
+
+// Helper function
+// Takes the text of a file and the name of the file
+// Returns the text with its sections in the order data, define, input, process, output, each moved together with
+// the comment lines directly above its header, while the blank lines between sections and the comments above the
+// first one stay where they are
+// The text is returned unchanged if it includes other files, or if a section header or 'end' shares its line with
+// something before it, since the sections could then not be moved line by line
+fn sections_sorter(text: &str, file: &str) -> String {
+    let tokens: Vec<Token> = lexer(String::from(text), file);
+    let lines: Vec<&str> = text.split('\n').collect();
+    let starts_line = |token: &Token| -> bool {
+        return lines[token.location.line - 1]
+            .chars()
+            .take(token.location.column - 1)
+            .all(char::is_whitespace);
+    };
+    let mut headers: Vec<(usize, usize)> = Vec::new();
+    let mut end: usize = lines.len();
+    for (i, token) in tokens.iter().enumerate() {
+        match token.token {
+            TokenTypes::INCLUDE => return String::from(text),
+            TokenTypes::END => {
+                if !starts_line(token) {
+                    return String::from(text);
+                }
+                end = token.location.line - 1;
+                break;
+            }
+            _ => {}
+        }
+        let header: bool = tokens.get(i + 1).map(|next| next.token) == Some(TokenTypes::COLON);
+        if let (true, Some(rank)) = (header, section_rank(token.token)) {
+            if !starts_line(token) {
+                return String::from(text);
+            }
+            headers.push((token.location.line - 1, rank));
+        }
+    }
+    if headers.is_empty() {
+        return String::from(text);
+    }
+
+    // Each section starts at the comment lines directly above its header and ends before its trailing blank lines
+    // Comments above the first header are about the whole file, so they stay at the top
+    let mut starts: Vec<usize> = vec![headers[0].0];
+    for k in 1..headers.len() {
+        let mut start: usize = headers[k].0;
+        while start > headers[k - 1].0 + 1 && lines[start - 1].trim_start().starts_with('#') {
+            start -= 1;
+        }
+        starts.push(start);
+    }
+    let mut sections: Vec<(usize, &[&str], &[&str])> = Vec::new();
+    for (k, (_, rank)) in headers.iter().enumerate() {
+        let next: usize = starts.get(k + 1).copied().unwrap_or(end);
+        let mut body_end: usize = next;
+        while body_end > starts[k] + 1 && lines[body_end - 1].trim().is_empty() {
+            body_end -= 1;
+        }
+        sections.push((*rank, &lines[starts[k]..body_end], &lines[body_end..next]));
+    }
+    let gaps: Vec<&[&str]> = sections.iter().map(|(_, _, gap)| *gap).collect();
+    sections.sort_by_key(|(rank, _, _)| *rank);
+
+    let mut sorted: Vec<&str> = lines[..starts[0]].to_vec();
+    for ((_, body, _), gap) in sections.iter().zip(gaps) {
+        sorted.extend_from_slice(body);
+        sorted.extend_from_slice(gap);
+    }
+    sorted.extend_from_slice(&lines[end..]);
+    return sorted.join("\n");
+}
+
+// Fixer
+// Applies the fixes of the errors of a file one error at a time, and writes the file back if any was applied
+// Takes the name of the file and the search path
+// Returns the number of fixes applied and the message of the first error left, if there is one
+// Function panics if the file cannot be read or written
+pub fn fixer(file: &str, search_path: &[PathBuf]) -> (usize, Option<String>) {
+    let mut text: String = match fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(error) => panic!(
+            "\n\n; FILE ERROR[E0019]!\n; Could not open the file '{}': {}.\n\n",
+            file, error
+        ),
+    };
+    let mut applied: usize = 0;
+
+    // The errors that are fixed are not printed
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut error: Option<String> = error_finder(&text, file, search_path);
+    while let Some(message) = &error {
+        let diagnostic: Diagnostic = match diagnostic_parser(message) {
+            Some(diagnostic) => diagnostic,
+            None => break,
+        };
+        let fixes: Vec<&Suggestion> = diagnostic
+            .suggestions
+            .iter()
+            .filter(|suggestion| suggestion.applicable && suggestion.location.file == file)
+            .collect();
+        // A constant used before the data section declaring it is fixed by sorting the sections
+        let sorting: bool = diagnostic.code.as_deref() == Some("E0025");
+        let fixed: String = if sorting {
+            sections_sorter(&text, file)
+        } else {
+            suggestions_applier(&text, &fixes)
+        };
+        // A fix that changes nothing would be found again and again
+        if fixed == text {
+            break;
+        }
+        text = fixed;
+        applied += if sorting { 1 } else { fixes.len() };
+        error = error_finder(&text, file, search_path);
+    }
+    panic::set_hook(hook);
+
+    if applied > 0 {
+        fs::write(file, &text)
+            .expect("\n\n; FILE ERROR[E0019]!\n; Could not write the fixed file!\n\n");
+    }
+    return (applied, error);
+}
@@ -0,0 +1,192 @@
+// Formatter for DA source
+// Pretty-prints a program, or a file of sections for 'include', in one canonical layout: section headers,
+// include directives and 'end.' start their own unindented line, every item of a section sits on its own line
+// indented by three spaces, and operators are spaced the same way everywhere
+// Comments are kept: a comment that ended a line of code still ends the line its code is printed on, and a
+// comment on its own line stays on its own line, indented like the code that follows it
+
+use crate::{Token, TokenTypes};
+
+const INDENT: &str = "   ";
+const CONTINUED: &str = "      ";
+
+// Helper function
+// Takes a TokenTypes and returns whether it is an arithmetic operator, a comparison, '=' or ','
+// A '-' right after one of these (or at the start of an item) is a negation
+fn is_operand_expected(token: TokenTypes) -> bool {
+    return matches!(
+        token,
+        TokenTypes::ASSIGN
+            | TokenTypes::COMMA
+            | TokenTypes::LPAREN
+            | TokenTypes::LBRACKET
+            | TokenTypes::PLUS
+            | TokenTypes::MINUS
+            | TokenTypes::TIMES
+            | TokenTypes::DIVIDE
+            | TokenTypes::LESS
+            | TokenTypes::GREATER
+            | TokenTypes::LESSEQUAL
+            | TokenTypes::GREATEREQUAL
+            | TokenTypes::EQUAL
+            | TokenTypes::NOTEQUAL
+    );
+}
+
+// Helper function
+// Takes the previous Token of an item, whether it was a negation, the Token that follows it, and the nesting depth
+// of parentheses and brackets
+// Returns whether a space separates the two Tokens
+// '=' and ':' are spaced at the top level of an item ('x : vector = 1') and tight inside parentheses
+// ('delimiter="\t"', 'a:3'), calls, columns and negations are tight, and everything else is spaced
+fn is_spaced(prev: &Token, negation: bool, curr: &Token, depth: usize) -> bool {
+    if negation {
+        return false;
+    }
+    match curr.token {
+        TokenTypes::COMMA
+        | TokenTypes::RPAREN
+        | TokenTypes::RBRACKET
+        | TokenTypes::LBRACKET
+        | TokenTypes::PERIOD => return false,
+        TokenTypes::LPAREN => {
+            return prev.token != TokenTypes::LPAREN
+                && !prev.lexeme.starts_with(|c: char| c.is_ascii_lowercase())
+        }
+        TokenTypes::ASSIGN | TokenTypes::COLON => return depth == 0,
+        _ => {}
+    }
+    match prev.token {
+        TokenTypes::LPAREN | TokenTypes::LBRACKET | TokenTypes::PERIOD => return false,
+        TokenTypes::ASSIGN | TokenTypes::COLON => return depth == 0,
+        _ => return true,
+    }
+}
+
+// Formatter
+// Takes the Tokens of a file that parses, comments included
+// Returns the formatted source, which ends with a newline
+pub fn formatter(tokens: &[Token]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line: String = String::new();
+    let mut trailing: Vec<String> = Vec::new();
+
+    let mut prev: Option<&Token> = None;
+    let mut negation: bool = false;
+    let mut depth: usize = 0;
+    let mut section: Option<TokenTypes> = None;
+    let mut in_item: bool = false;
+    let mut assigned: bool = false;
+    let mut continued: bool = false;
+    let mut ends_line: bool = false;
+
+    // Ends the current line, if it holds any code, followed by the comments that ended its lines in the source
+    let flush = |lines: &mut Vec<String>, line: &mut String, trailing: &mut Vec<String>| {
+        if !line.trim().is_empty() {
+            for comment in trailing.drain(..) {
+                line.push(' ');
+                line.push_str(&comment);
+            }
+            lines.push(line.clone());
+        }
+        line.clear();
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.token == TokenTypes::COMMENT {
+            let same_line: bool =
+                prev.is_some_and(|prev| prev.location.line == token.location.line);
+            let next: Option<&Token> = tokens[i + 1..]
+                .iter()
+                .find(|next| next.token != TokenTypes::COMMENT);
+            if same_line && !line.trim().is_empty() {
+                // The line goes on up to the ',' that ends the item, or else breaks after the comment
+                trailing.push(token.lexeme.clone());
+                if next.is_none_or(|next| next.token != TokenTypes::COMMA || depth > 0) {
+                    flush(&mut lines, &mut line, &mut trailing);
+                    continued = true;
+                }
+            } else if same_line && !lines.is_empty() {
+                let last: &mut String = lines.last_mut().unwrap();
+                last.push(' ');
+                last.push_str(&token.lexeme);
+            } else {
+                // A comment on its own line is indented like the code that follows it
+                flush(&mut lines, &mut line, &mut trailing);
+                let indent: &str = match next {
+                    Some(next) if !crate::is_section_token(next.token) => {
+                        if in_item {
+                            continued = true;
+                            CONTINUED
+                        } else {
+                            INDENT
+                        }
+                    }
+                    _ => "",
+                };
+                lines.push(format!("{indent}{}", token.lexeme));
+            }
+            continue;
+        }
+
+        if crate::is_section_token(token.token) {
+            // Section headers, include directives and 'end.' end the last item of a section
+            flush(&mut lines, &mut line, &mut trailing);
+            in_item = false;
+            assigned = false;
+            continued = false;
+            line.push_str(&token.lexeme);
+            if token.token != TokenTypes::INCLUDE && token.token != TokenTypes::END {
+                section = Some(token.token);
+            }
+            ends_line = true;
+            prev = Some(token);
+            continue;
+        }
+        if ends_line {
+            // The ':' of a header, the file name of an include, or the '.' of 'end.' ends the line
+            if token.token == TokenTypes::STRING {
+                line.push(' ');
+            }
+            line.push_str(&token.lexeme);
+            flush(&mut lines, &mut line, &mut trailing);
+            ends_line = false;
+            prev = Some(token);
+            continue;
+        }
+
+        if line.is_empty() {
+            line.push_str(if continued { CONTINUED } else { INDENT });
+        } else if let Some(prev) = prev {
+            if is_spaced(prev, negation, token, depth) {
+                line.push(' ');
+            }
+        }
+        line.push_str(&token.lexeme);
+
+        negation = token.token == TokenTypes::MINUS
+            && (!in_item || prev.is_some_and(|prev| is_operand_expected(prev.token)));
+        in_item = true;
+        match token.token {
+            TokenTypes::LPAREN | TokenTypes::LBRACKET => depth += 1,
+            TokenTypes::RPAREN | TokenTypes::RBRACKET => depth = depth.saturating_sub(1),
+            TokenTypes::ASSIGN if depth == 0 => assigned = true,
+            // A ',' at the top level ends an item, except between the identifiers of 'a, b, rsq = ...'
+            TokenTypes::COMMA
+                if depth == 0 && (section != Some(TokenTypes::PROCESS) || assigned) =>
+            {
+                flush(&mut lines, &mut line, &mut trailing);
+                in_item = false;
+                assigned = false;
+                continued = false;
+            }
+            _ => {}
+        }
+        prev = Some(token);
+    }
+    flush(&mut lines, &mut line, &mut trailing);
+
+    let mut formatted: String = lines.join("\n");
+    formatted.push('\n');
+    return formatted;
+}
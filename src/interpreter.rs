@@ -0,0 +1,766 @@
+// Interpreter for DA programs
+// Runs a checked Program directly: loads its inputs, evaluates its process ops, and performs its output ops
+// Runtime errors panic with a '; RUNTIME ERROR[code]!' message, like the errors of the other phases
+
+use crate::ast::{is_numeric, Column, Define, Expr, OutputOp, PrintItem, Program, Source};
+use crate::json::{json_parser, Json};
+use crate::svg::{histogram_svg, scatter_svg};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+
+// Value enum stores the value of a data entry at runtime
+#[derive(Clone)]
+pub enum Value {
+    Number(f64),
+    Vector(Vec<f64>),
+    Table(Table),
+}
+
+// Table struct stores the columns of a table and, if the file had a header row, their names
+#[derive(Clone)]
+pub struct Table {
+    pub names: Vec<String>,
+    pub columns: Vec<Vec<f64>>,
+}
+
+// Helper function
+// Takes a STRING lexeme and returns its contents without the quotes and with escape sequences resolved
+pub fn unescape(lexeme: &str) -> String {
+    let mut output: String = String::new();
+    let mut chars = lexeme[1..lexeme.len() - 1].chars();
+    while let Some(curr_char) = chars.next() {
+        if curr_char == '\\' {
+            match chars.next() {
+                Some('t') => output.push('\t'),
+                Some('n') => output.push('\n'),
+                Some('r') => output.push('\r'),
+                Some(other) => output.push(other),
+                None => output.push('\\'),
+            }
+        } else {
+            output.push(curr_char);
+        }
+    }
+    return output;
+}
+
+// Helper function
+// Takes the name of a file and returns its contents
+// Function panics if the file cannot be read
+fn file_reader(file: &str) -> String {
+    match fs::read_to_string(file) {
+        Ok(contents) => return contents,
+        Err(error) => panic!(
+            "\n\n; RUNTIME ERROR[E0020]!\n; Could not read the file '{}': {}.\n\n",
+            file, error
+        ),
+    }
+}
+
+// Helper function
+// Takes a field of an input file, the file name, and the line number, and returns the field as a number
+// Function panics if the field is not a number
+fn number_parser(field: &str, file: &str, line: usize) -> f64 {
+    match field.trim().parse::<f64>() {
+        Ok(number) => return number,
+        Err(_) => panic!(
+            "\n\n; RUNTIME ERROR[E0020]!\n; '{}' on line {} of '{}' is not a number.\n\n",
+            field.trim(),
+            line,
+            file
+        ),
+    }
+}
+
+// Helper function
+// Takes the name of a delimited file and its delimiter
+// Returns the line number and the fields of every non-empty line, trimmed and without surrounding quotes
+// Function panics if the file cannot be read
+fn rows_reader(file: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    return file_reader(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields: Vec<String> = line
+                .split(delimiter)
+                .map(|field| field.trim().trim_matches('"').to_string())
+                .collect();
+            (i + 1, fields)
+        })
+        .collect();
+}
+
+// Input Evaluator
+// Loads the vector or table an input op reads
+// Takes the Source of the input op
+// Returns the loaded Value
+// Function panics if the file cannot be read or does not hold the expected numbers
+pub fn input_evaluator(source: &Source) -> Value {
+    match source {
+        Source::Csv {
+            file,
+            header,
+            column,
+            delimiter,
+        } => {
+            let file: String = unescape(file);
+            let delimiter: char = match delimiter {
+                Some(delimiter) => unescape(delimiter).chars().next().unwrap(),
+                None => ',',
+            };
+            let rows: Vec<(usize, Vec<String>)> = rows_reader(&file, delimiter);
+
+            // A column name is looked up in the first row
+            let index: usize = match column {
+                Column::Name(_) => {
+                    let name: String = unescape(&column.to_string());
+                    match rows
+                        .first()
+                        .and_then(|(_, fields)| fields.iter().position(|field| *field == name))
+                    {
+                        Some(index) => index,
+                        None => panic!(
+                            "\n\n; RUNTIME ERROR[E0020]!\n; The header of '{}' has no column named '{}'.\n\n",
+                            file, name
+                        ),
+                    }
+                }
+                Column::Index(index) => *index,
+            };
+
+            let skip: usize = if *header { 1 } else { 0 };
+            return Value::Vector(
+                rows.iter()
+                    .skip(skip)
+                    .map(|(line, fields)| match fields.get(index) {
+                        Some(field) => number_parser(field, &file, *line),
+                        None => panic!(
+                            "\n\n; RUNTIME ERROR[E0020]!\n; Line {} of '{}' has no column {}.\n\n",
+                            line, file, index
+                        ),
+                    })
+                    .collect(),
+            );
+        }
+        Source::Table { file, header } => {
+            let file: String = unescape(file);
+            let mut rows: Vec<(usize, Vec<String>)> = rows_reader(&file, ',');
+            let names: Vec<String> = if *header && !rows.is_empty() {
+                rows.remove(0).1
+            } else {
+                Vec::new()
+            };
+            let width: usize = match rows.first() {
+                Some((_, fields)) => fields.len(),
+                None => names.len(),
+            };
+            let mut columns: Vec<Vec<f64>> = vec![Vec::new(); width];
+            for (line, fields) in &rows {
+                assert!(
+                    fields.len() == width,
+                    "\n\n; RUNTIME ERROR[E0020]!\n; Line {} of '{}' has {} fields, expected {}.\n\n",
+                    line,
+                    file,
+                    fields.len(),
+                    width
+                );
+                for (column, field) in columns.iter_mut().zip(fields) {
+                    column.push(number_parser(field, &file, *line));
+                }
+            }
+            return Value::Table(Table { names, columns });
+        }
+        Source::Json { file, field } => {
+            let file: String = unescape(file);
+            let json: Json = match json_parser(&file_reader(&file)) {
+                Ok(json) => json,
+                Err(error) => panic!(
+                    "\n\n; RUNTIME ERROR[E0020]!\n; '{}' is not valid JSON: {}.\n\n",
+                    file, error
+                ),
+            };
+            let elements: Vec<Json> = match json {
+                Json::Array(elements) => elements,
+                _ => panic!(
+                    "\n\n; RUNTIME ERROR[E0020]!\n; '{}' does not hold a JSON array.\n\n",
+                    file
+                ),
+            };
+            let field: Option<String> = field.as_ref().map(|field| unescape(field));
+            let values: Vec<f64> = elements
+                .iter()
+                .enumerate()
+                .map(|(i, element)| {
+                    let value: Option<&Json> = match &field {
+                        Some(field) => element.get(field),
+                        None => Some(element),
+                    };
+                    match value {
+                        Some(Json::Number(number)) => *number,
+                        Some(other) => panic!(
+                            "\n\n; RUNTIME ERROR[E0020]!\n; Element {} of '{}' holds {} where a number is expected.\n\n",
+                            i, file, other
+                        ),
+                        None => panic!(
+                            "\n\n; RUNTIME ERROR[E0020]!\n; Element {} of '{}' has no field '{}'.\n\n",
+                            i,
+                            file,
+                            field.as_ref().unwrap()
+                        ),
+                    }
+                })
+                .collect();
+            return Value::Vector(values);
+        }
+        Source::Lines { file } => {
+            let file: String = unescape(file);
+            return Value::Vector(
+                file_reader(&file)
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, line)| number_parser(line, &file, i + 1))
+                    .collect(),
+            );
+        }
+    }
+}
+
+// Helper function
+// Takes a vector and returns its mean
+// Function panics if the vector is empty
+fn mean(values: &[f64]) -> f64 {
+    assert!(
+        !values.is_empty(),
+        "\n\n; RUNTIME ERROR[E0021]!\n; Cannot compute the mean of an empty vector.\n\n"
+    );
+    return values.iter().sum::<f64>() / values.len() as f64;
+}
+
+// Helper function
+// Takes a vector and returns its sample standard deviation
+// Function panics if the vector has fewer than two elements
+fn stddev(values: &[f64]) -> f64 {
+    assert!(
+        values.len() > 1,
+        "\n\n; RUNTIME ERROR[E0021]!\n; Cannot compute the standard deviation of fewer than two values.\n\n"
+    );
+    let m: f64 = mean(values);
+    let squares: f64 = values.iter().map(|value| (value - m) * (value - m)).sum();
+    return (squares / (values.len() - 1) as f64).sqrt();
+}
+
+// Helper function
+// Takes two vectors and returns the sums of squares and cross-products around their means
+// Function panics if the vectors are empty or differ in length
+fn sums_of_squares(x: &[f64], y: &[f64]) -> (f64, f64, f64) {
+    assert!(
+        x.len() == y.len(),
+        "\n\n; RUNTIME ERROR[E0022]!\n; Vectors of lengths {} and {} cannot be paired.\n\n",
+        x.len(),
+        y.len()
+    );
+    let (x_mean, y_mean) = (mean(x), mean(y));
+    let mut sxx: f64 = 0.0;
+    let mut syy: f64 = 0.0;
+    let mut sxy: f64 = 0.0;
+    for (x_value, y_value) in x.iter().zip(y) {
+        sxx += (x_value - x_mean) * (x_value - x_mean);
+        syy += (y_value - y_mean) * (y_value - y_mean);
+        sxy += (x_value - x_mean) * (y_value - y_mean);
+    }
+    return (sxx, syy, sxy);
+}
+
+// Helper function
+// Takes two vectors and returns the intercept a and the slope b of the least-squares line y = a + b * x
+pub fn regression(x: &[f64], y: &[f64]) -> (f64, f64) {
+    let (sxx, _, sxy) = sums_of_squares(x, y);
+    let slope: f64 = sxy / sxx;
+    return (mean(y) - slope * mean(x), slope);
+}
+
+// Helper function
+// Takes a table of predictors and a vector of responses, and fits y = b0 + b1 * x1 + ... + bk * xk by least
+// squares, solving the normal equations with Gaussian elimination and partial pivoting
+// Returns the coefficients b0, b1, ..., bk
+// Function panics if the lengths differ or the predictors are linearly dependent
+fn multiple_regression(table: &Table, y: &[f64]) -> Vec<f64> {
+    for column in &table.columns {
+        assert!(
+            column.len() == y.len(),
+            "\n\n; RUNTIME ERROR[E0022]!\n; A table with {} rows cannot be paired with a vector of length {}.\n\n",
+            column.len(),
+            y.len()
+        );
+    }
+    let size: usize = table.columns.len() + 1;
+    let row = |i: usize| -> Vec<f64> {
+        let mut row: Vec<f64> = vec![1.0];
+        row.extend(table.columns.iter().map(|column| column[i]));
+        return row;
+    };
+
+    // The augmented matrix [X'X | X'y]
+    let mut matrix: Vec<Vec<f64>> = vec![vec![0.0; size + 1]; size];
+    for (i, y_value) in y.iter().enumerate() {
+        let x: Vec<f64> = row(i);
+        for j in 0..size {
+            for k in 0..size {
+                matrix[j][k] += x[j] * x[k];
+            }
+            matrix[j][size] += x[j] * y_value;
+        }
+    }
+
+    for j in 0..size {
+        let pivot: usize = (j..size)
+            .max_by(|a, b| matrix[*a][j].abs().total_cmp(&matrix[*b][j].abs()))
+            .unwrap();
+        assert!(
+            matrix[pivot][j].abs() > 1e-12,
+            "\n\n; RUNTIME ERROR[E0021]!\n; The regression has no unique solution, the columns of the table are linearly dependent or there are too few rows.\n\n"
+        );
+        matrix.swap(j, pivot);
+        let pivot_row: Vec<f64> = matrix[j].clone();
+        for (k, row) in matrix.iter_mut().enumerate() {
+            if k != j {
+                let factor: f64 = row[j] / pivot_row[j];
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_row).skip(j) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+    return (0..size).map(|j| matrix[j][size] / matrix[j][j]).collect();
+}
+
+// Helper function
+// Takes two vectors and returns their Pearson correlation coefficient
+fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let (sxx, syy, sxy) = sums_of_squares(x, y);
+    return sxy / (sxx * syy).sqrt();
+}
+
+// Helper function
+// Takes an arithmetic operator and two numbers, and returns the result of the operation
+fn arithmetic(op: char, lhs: f64, rhs: f64) -> f64 {
+    return match op {
+        '+' => lhs + rhs,
+        '-' => lhs - rhs,
+        '*' => lhs * rhs,
+        _ => lhs / rhs,
+    };
+}
+
+// Helper function
+// Takes a comparison lexeme and two numbers, and returns whether the comparison holds
+fn comparison(comparison: &str, lhs: f64, rhs: f64) -> bool {
+    return match comparison {
+        "<" => lhs < rhs,
+        ">" => lhs > rhs,
+        "<=" => lhs <= rhs,
+        ">=" => lhs >= rhs,
+        "==" => lhs == rhs,
+        _ => lhs != rhs,
+    };
+}
+
+// Helper function
+// Takes a Value and returns it as a vector
+// Function panics if the value is a number or a table
+fn as_vector(value: Value) -> Vec<f64> {
+    match value {
+        Value::Vector(values) => return values,
+        Value::Number(number) => panic!(
+            "\n\n; RUNTIME ERROR[E0011]!\n; Expected a vector, found the number {}.\n\n",
+            number
+        ),
+        Value::Table(_) => {
+            panic!("\n\n; RUNTIME ERROR[E0011]!\n; Expected a vector, found a table.\n\n")
+        }
+    }
+}
+
+// Helper function
+// Takes a Value and returns it as a number
+// Function panics if the value is a vector or a table
+fn as_number(value: Value) -> f64 {
+    match value {
+        Value::Number(number) => return number,
+        Value::Vector(_) => {
+            panic!("\n\n; RUNTIME ERROR[E0011]!\n; Expected a number, found a vector.\n\n")
+        }
+        Value::Table(_) => {
+            panic!("\n\n; RUNTIME ERROR[E0011]!\n; Expected a number, found a table.\n\n")
+        }
+    }
+}
+
+// Helper function
+// Takes a Value and returns it as a table
+// Function panics if the value is a number or a vector
+fn as_table(value: Value) -> Table {
+    match value {
+        Value::Table(table) => return table,
+        _ => panic!("\n\n; RUNTIME ERROR[E0011]!\n; Expected a table.\n\n"),
+    }
+}
+
+// Helper function
+// Takes a table and a Column, and returns the values of the column
+// Function panics if the table has no such column
+fn column_evaluator(table: Table, column: &Column) -> Vec<f64> {
+    let index: usize = match column {
+        Column::Index(index) => *index,
+        Column::Name(name) => match table.names.iter().position(|other| other == name) {
+            Some(index) => index,
+            None if table.names.is_empty() => panic!(
+                "\n\n; RUNTIME ERROR[E0020]!\n; The table has no header row, so column '{}' must be picked by index.\n\n",
+                name
+            ),
+            None => panic!(
+                "\n\n; RUNTIME ERROR[E0020]!\n; The table has no column named '{}'.\n\n",
+                name
+            ),
+        },
+    };
+    assert!(
+        index < table.columns.len(),
+        "\n\n; RUNTIME ERROR[E0020]!\n; Column {} is out of range for a table with {} columns.\n\n",
+        index,
+        table.columns.len()
+    );
+    return table.columns[index].clone();
+}
+
+// Helper function
+// Takes an identifier and the values computed so far, and returns the value of the identifier
+// Function panics if the identifier has not been given a value
+pub fn lookup(id: &String, env: &HashMap<String, Value>) -> Value {
+    match env.get(id) {
+        Some(value) => return value.clone(),
+        None => panic!(
+            "\n\n; RUNTIME ERROR[E0012]!\n; '{}' is used before it is given a value.\n\n",
+            id
+        ),
+    }
+}
+
+// Expression Evaluator
+// Takes an Expr, the values computed so far, and the user functions
+// Returns the Value of the expression
+// Function panics if runtime errors are found
+pub fn expression_evaluator(
+    expr: &Expr,
+    env: &HashMap<String, Value>,
+    functions: &HashMap<String, &Define>,
+) -> Value {
+    match expr {
+        Expr::Num(num) => return Value::Number(num.parse().unwrap()),
+        Expr::Id(id) => return lookup(id, env),
+        Expr::Call(func, args) => {
+            let mut args: Vec<Value> = args
+                .iter()
+                .map(|arg| expression_evaluator(arg, env, functions))
+                .collect();
+
+            if let Some(define) = functions.get(func) {
+                let mut body_env: HashMap<String, Value> = HashMap::new();
+                for (param, arg) in define.params.iter().zip(args) {
+                    body_env.insert(param.clone(), arg);
+                }
+                return expression_evaluator(&define.body, &body_env, functions);
+            }
+
+            if func == "regression" {
+                let table: Table = as_table(args.remove(0));
+                let y: Vec<f64> = as_vector(args.remove(0));
+                return Value::Vector(multiple_regression(&table, &y));
+            }
+
+            let first: Vec<f64> = as_vector(args.remove(0));
+            match func.as_str() {
+                "mean" => return Value::Number(mean(&first)),
+                "stddev" => return Value::Number(stddev(&first)),
+                "abs" => return Value::Vector(first.iter().map(|value| value.abs()).collect()),
+                "log" => return Value::Vector(first.iter().map(|value| value.ln()).collect()),
+                "slice" => {
+                    let start: f64 = as_number(args.remove(0));
+                    let end: f64 = as_number(args.remove(0));
+                    assert!(
+                        start >= 0.0 && start <= end && end <= first.len() as f64,
+                        "\n\n; RUNTIME ERROR[E0022]!\n; Cannot slice [{}, {}) out of a vector of length {}.\n\n",
+                        start,
+                        end,
+                        first.len()
+                    );
+                    return Value::Vector(first[start as usize..end as usize].to_vec());
+                }
+                _ => {}
+            }
+
+            let second: Vec<f64> = as_vector(args.remove(0));
+            match func.as_str() {
+                "concat" => return Value::Vector([first, second].concat()),
+                "correlation" => return Value::Number(correlation(&first, &second)),
+                "regressiona" => return Value::Number(regression(&first, &second).0),
+                _ => return Value::Number(regression(&first, &second).1),
+            }
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs: Value = expression_evaluator(lhs, env, functions);
+            let rhs: Value = expression_evaluator(rhs, env, functions);
+            match (lhs, rhs) {
+                (Value::Number(lhs), Value::Number(rhs)) => {
+                    return Value::Number(arithmetic(*op, lhs, rhs));
+                }
+                (Value::Vector(lhs), Value::Number(rhs)) => {
+                    return Value::Vector(lhs.iter().map(|e| arithmetic(*op, *e, rhs)).collect());
+                }
+                (Value::Number(lhs), Value::Vector(rhs)) => {
+                    return Value::Vector(rhs.iter().map(|e| arithmetic(*op, lhs, *e)).collect());
+                }
+                (Value::Vector(lhs), Value::Vector(rhs)) => {
+                    assert!(
+                        lhs.len() == rhs.len(),
+                        "\n\n; RUNTIME ERROR[E0022]!\n; Operator '{}' cannot combine vectors of lengths {} and {}.\n\n",
+                        op,
+                        lhs.len(),
+                        rhs.len()
+                    );
+                    return Value::Vector(
+                        lhs.iter()
+                            .zip(rhs)
+                            .map(|(l, r)| arithmetic(*op, *l, r))
+                            .collect(),
+                    );
+                }
+                _ => panic!(
+                    "\n\n; RUNTIME ERROR[E0011]!\n; Operator '{}' cannot be applied to a table.\n\n",
+                    op
+                ),
+            }
+        }
+        Expr::Negate(operand) => {
+            match expression_evaluator(operand, env, functions) {
+                Value::Number(number) => return Value::Number(-number),
+                Value::Vector(values) => return Value::Vector(values.iter().map(|e| -e).collect()),
+                Value::Table(_) => {
+                    panic!("\n\n; RUNTIME ERROR[E0011]!\n; Operator '-' cannot be applied to a table.\n\n")
+                }
+            }
+        }
+        Expr::Filter(vector, op, threshold) => {
+            let values: Vec<f64> = as_vector(expression_evaluator(vector, env, functions));
+            let threshold: f64 = as_number(expression_evaluator(threshold, env, functions));
+            return Value::Vector(
+                values
+                    .into_iter()
+                    .filter(|value| comparison(op, *value, threshold))
+                    .collect(),
+            );
+        }
+        Expr::Column(table, column) => {
+            let table: Table = as_table(expression_evaluator(table, env, functions));
+            return Value::Vector(column_evaluator(table, column));
+        }
+    }
+}
+
+// Results Evaluator
+// Evaluates the right-hand side of a process op, which may be a call with several results
+// 'regression(x, y)' with two vectors gives the intercept, the slope, and the squared correlation
+// Takes an Expr, the values computed so far, and the user functions
+// Returns the Values of the results
+// Function panics if runtime errors are found
+pub fn results_evaluator(
+    expr: &Expr,
+    env: &HashMap<String, Value>,
+    functions: &HashMap<String, &Define>,
+) -> Vec<Value> {
+    if let Expr::Call(func, args) = expr {
+        if func == "regression" {
+            let args: Vec<Value> = args
+                .iter()
+                .map(|arg| expression_evaluator(arg, env, functions))
+                .collect();
+            if let [Value::Vector(x), Value::Vector(y)] = args.as_slice() {
+                let (intercept, slope) = regression(x, y);
+                let r: f64 = correlation(x, y);
+                return vec![
+                    Value::Number(intercept),
+                    Value::Number(slope),
+                    Value::Number(r * r),
+                ];
+            }
+        }
+    }
+    return vec![expression_evaluator(expr, env, functions)];
+}
+
+// Helper function
+// Takes a Value, an optional number of decimals, and the separator between vector elements
+// Returns the value as text
+pub fn value_formatter(value: &Value, precision: Option<usize>, separator: &str) -> String {
+    let number_formatter = |number: &f64| -> String {
+        match precision {
+            Some(precision) => format!("{number:.precision$}"),
+            None => format!("{number}"),
+        }
+    };
+    match value {
+        Value::Number(number) => return number_formatter(number),
+        Value::Vector(values) => {
+            let values: Vec<String> = values.iter().map(number_formatter).collect();
+            return values.join(separator);
+        }
+        Value::Table(table) => {
+            let columns: Vec<String> = table
+                .columns
+                .iter()
+                .map(|column| {
+                    let values: Vec<String> = column.iter().map(number_formatter).collect();
+                    format!("[{}]", values.join(separator))
+                })
+                .collect();
+            return columns.join(separator);
+        }
+    }
+}
+
+// Helper function
+// Takes PrintItems, the values computed so far, and the separator between items
+// Returns the items as one line of text
+fn printitems_formatter(
+    items: &[PrintItem],
+    env: &HashMap<String, Value>,
+    separator: &str,
+) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            PrintItem::Str(str) => unescape(str),
+            PrintItem::Id(id, precision) => {
+                let value: Value = lookup(id, env);
+                if separator.is_empty() {
+                    if let Value::Vector(_) = value {
+                        return format!("[{}]", value_formatter(&value, *precision, ", "));
+                    }
+                }
+                value_formatter(&value, *precision, separator)
+            }
+        })
+        .collect();
+    return items.join(separator);
+}
+
+// Helper function
+// Takes a file name and its new contents, and writes (or appends) the contents to the file
+// Function panics if the file cannot be written
+fn file_writer(file: &str, contents: &str, append: bool) {
+    let result = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(file)
+        .and_then(|mut handle| handle.write_all(contents.as_bytes()));
+    if let Err(error) = result {
+        panic!(
+            "\n\n; RUNTIME ERROR[E0023]!\n; Could not write the file '{}': {}.\n\n",
+            file, error
+        );
+    }
+}
+
+// OutputOp Evaluator
+// Performs one output op: prints to stdout, writes a file, or renders an SVG chart
+// Takes the OutputOp and the values computed so far
+// Function panics if runtime errors are found
+pub fn outputop_evaluator(outputop: &OutputOp, env: &HashMap<String, Value>) {
+    match outputop {
+        OutputOp::Str(str) => println!("{}", unescape(str)),
+        OutputOp::Id(id) => {
+            let value: Value = lookup(id, env);
+            match value {
+                Value::Number(_) => println!("{}", value_formatter(&value, None, "")),
+                _ => println!("[{}]", value_formatter(&value, None, ", ")),
+            }
+        }
+        OutputOp::Print(items) => println!("{}", printitems_formatter(items, env, "")),
+        OutputOp::Write {
+            file,
+            items,
+            append,
+        } => {
+            let line: String = printitems_formatter(items, env, ",");
+            file_writer(&unescape(file), &format!("{line}\n"), *append);
+        }
+        OutputOp::Plot { x, y, file } => {
+            let x: Vec<f64> = as_vector(lookup(x, env));
+            let y: Vec<f64> = as_vector(lookup(y, env));
+            let (intercept, slope) = regression(&x, &y);
+            file_writer(
+                &unescape(file),
+                &scatter_svg(&x, &y, intercept, slope),
+                false,
+            );
+        }
+        OutputOp::Histogram { values, file } => {
+            let values: Vec<f64> = as_vector(lookup(values, env));
+            assert!(
+                !values.is_empty(),
+                "\n\n; RUNTIME ERROR[E0021]!\n; Cannot draw the histogram of an empty vector.\n\n"
+            );
+            file_writer(&unescape(file), &histogram_svg(&values), false);
+        }
+    }
+}
+
+// Interpreter
+// Runs a checked Program: evaluates the constants and initialisers, loads every input, evaluates every process op
+// in order, and performs every output op
+// Takes a Program
+// Function panics if runtime errors are found
+pub fn interpreter(program: &Program) {
+    let mut env: HashMap<String, Value> = HashMap::new();
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        functions.insert(define.name.clone(), define);
+    }
+
+    // String and boolean constants were replaced by their values in the 'read' arguments when parsing
+    for constant in &program.consts {
+        if is_numeric(constant) {
+            env.insert(
+                constant.id.clone(),
+                Value::Number(constant.value.parse().unwrap()),
+            );
+        }
+    }
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            let value: Value = expression_evaluator(init, &env, &functions);
+            env.insert(datadef.id.clone(), value);
+        }
+    }
+
+    for inputop in &program.inputops {
+        env.insert(inputop.id.clone(), input_evaluator(&inputop.source));
+    }
+
+    for processop in &program.processops {
+        let values: Vec<Value> = results_evaluator(&processop.expr, &env, &functions);
+        for (id, value) in processop.ids.iter().zip(values) {
+            env.insert(id.clone(), value);
+        }
+    }
+
+    for (outputop, _) in &program.outputops {
+        outputop_evaluator(outputop, &env);
+    }
+}
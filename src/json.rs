@@ -0,0 +1,285 @@
+// Minimal JSON support
+// Parses JSON text into a Json value without any external dependencies
+
+// Json enum stores a parsed JSON value
+// Objects keep their members in source order
+#[derive(Clone)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    // Takes a key and returns the member of an object with that key, if any
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // Returns the value as JSON text with every element and member on its own line, indented by two spaces
+    // per level, e.g. for dumps that people read
+    pub fn pretty(&self) -> String {
+        return pretty_printer(self, 0);
+    }
+}
+
+// Json values are displayed as compact JSON text
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write!(f, "\"{}\"", escape(value)),
+            Json::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(","))
+            }
+            Json::Object(members) => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(name, value)| format!("\"{}\":{}", escape(name), value))
+                    .collect();
+                write!(f, "{{{}}}", members.join(","))
+            }
+        }
+    }
+}
+
+// Helper function
+// Takes a list of members and returns a JSON object with them
+pub fn object(members: Vec<(&str, Json)>) -> Json {
+    return Json::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (String::from(name), value))
+            .collect(),
+    );
+}
+
+// Helper function
+// Takes a text and returns it as a JSON string
+pub fn string(text: &str) -> Json {
+    return Json::String(String::from(text));
+}
+
+// Helper function
+// Takes an unsigned integer and returns it as a JSON number
+pub fn number(value: usize) -> Json {
+    return Json::Number(value as f64);
+}
+
+// Helper function
+// Takes a Json value and the nesting level it is printed at, and returns it as indented JSON text
+// Arrays and objects that hold no arrays or objects stay on one line, e.g. '{"line": 2, "column": 4}'
+fn pretty_printer(json: &Json, level: usize) -> String {
+    let is_nested = |value: &Json| matches!(value, Json::Array(_) | Json::Object(_));
+    let (open, close, items): (char, char, Vec<String>) = match json {
+        Json::Array(elements) if elements.iter().any(is_nested) => (
+            '[',
+            ']',
+            elements
+                .iter()
+                .map(|element| pretty_printer(element, level + 1))
+                .collect(),
+        ),
+        Json::Object(members) if members.iter().any(|(_, value)| is_nested(value)) => (
+            '{',
+            '}',
+            members
+                .iter()
+                .map(|(name, value)| {
+                    format!("\"{}\": {}", escape(name), pretty_printer(value, level + 1))
+                })
+                .collect(),
+        ),
+        Json::Array(elements) => {
+            let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+            return format!("[{}]", elements.join(", "));
+        }
+        Json::Object(members) => {
+            let members: Vec<String> = members
+                .iter()
+                .map(|(name, value)| format!("\"{}\": {}", escape(name), value))
+                .collect();
+            return format!("{{{}}}", members.join(", "));
+        }
+        _ => return json.to_string(),
+    };
+    let indent: String = "  ".repeat(level + 1);
+    return format!(
+        "{open}\n{indent}{}\n{}{close}",
+        items.join(&format!(",\n{indent}")),
+        "  ".repeat(level)
+    );
+}
+
+// Helper function
+// Takes a string and returns it with the characters JSON requires escaped
+fn escape(text: &str) -> String {
+    let mut output: String = String::new();
+    for curr_char in text.chars() {
+        match curr_char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    return output;
+}
+
+// Helper function
+// Takes the characters of the JSON text and an index, and returns the index of the next non-whitespace character
+fn skip_whitespace(chars: &[char], start_index: usize) -> usize {
+    let mut i: usize = start_index;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    return i;
+}
+
+// String Parser
+// Parses a JSON string whose opening quote is at the given index
+// Takes the characters of the JSON text and an index
+// Returns a tuple with the index after the closing quote and the string, or an error message
+fn string_parser(chars: &[char], start_index: usize) -> Result<(usize, String), String> {
+    let mut i: usize = start_index + 1;
+    let mut output: String = String::new();
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return Ok((i + 1, output)),
+            '\\' if i + 1 < chars.len() => {
+                match chars[i + 1] {
+                    'n' => output.push('\n'),
+                    't' => output.push('\t'),
+                    'r' => output.push('\r'),
+                    'b' => output.push('\u{8}'),
+                    'f' => output.push('\u{c}'),
+                    'u' if i + 5 < chars.len() => {
+                        let hex: String = chars[i + 2..i + 6].iter().collect();
+                        let code: u32 = u32::from_str_radix(&hex, 16)
+                            .map_err(|_| format!("invalid escape '\\u{hex}'"))?;
+                        output.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        i += 4;
+                    }
+                    other => output.push(other),
+                }
+                i += 2;
+            }
+            other => {
+                output.push(other);
+                i += 1;
+            }
+        }
+    }
+    return Err(String::from("unterminated string"));
+}
+
+// Value Parser
+// Parses the JSON value starting at the given index
+// Takes the characters of the JSON text and an index
+// Returns a tuple with the index after the value and the value, or an error message
+fn value_parser(chars: &[char], start_index: usize) -> Result<(usize, Json), String> {
+    let mut i: usize = skip_whitespace(chars, start_index);
+    if i >= chars.len() {
+        return Err(String::from("unexpected end of input"));
+    }
+
+    match chars[i] {
+        '{' => {
+            let mut members: Vec<(String, Json)> = Vec::new();
+            i = skip_whitespace(chars, i + 1);
+            if i < chars.len() && chars[i] == '}' {
+                return Ok((i + 1, Json::Object(members)));
+            }
+            loop {
+                i = skip_whitespace(chars, i);
+                if i >= chars.len() || chars[i] != '"' {
+                    return Err(format!("expected a member name at offset {i}"));
+                }
+                let name: String;
+                let value: Json;
+                (i, name) = string_parser(chars, i)?;
+                i = skip_whitespace(chars, i);
+                if i >= chars.len() || chars[i] != ':' {
+                    return Err(format!("expected ':' at offset {i}"));
+                }
+                (i, value) = value_parser(chars, i + 1)?;
+                members.push((name, value));
+                i = skip_whitespace(chars, i);
+                if i < chars.len() && chars[i] == ',' {
+                    i += 1;
+                } else if i < chars.len() && chars[i] == '}' {
+                    return Ok((i + 1, Json::Object(members)));
+                } else {
+                    return Err(format!("expected ',' or '}}' at offset {i}"));
+                }
+            }
+        }
+        '[' => {
+            let mut elements: Vec<Json> = Vec::new();
+            i = skip_whitespace(chars, i + 1);
+            if i < chars.len() && chars[i] == ']' {
+                return Ok((i + 1, Json::Array(elements)));
+            }
+            loop {
+                let element: Json;
+                (i, element) = value_parser(chars, i)?;
+                elements.push(element);
+                i = skip_whitespace(chars, i);
+                if i < chars.len() && chars[i] == ',' {
+                    i += 1;
+                } else if i < chars.len() && chars[i] == ']' {
+                    return Ok((i + 1, Json::Array(elements)));
+                } else {
+                    return Err(format!("expected ',' or ']' at offset {i}"));
+                }
+            }
+        }
+        '"' => {
+            let string: String;
+            (i, string) = string_parser(chars, i)?;
+            return Ok((i, Json::String(string)));
+        }
+        _ => {
+            let start: usize = i;
+            while i < chars.len() && !",]} \t\r\n".contains(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            return match word.as_str() {
+                "null" => Ok((i, Json::Null)),
+                "true" => Ok((i, Json::Bool(true))),
+                "false" => Ok((i, Json::Bool(false))),
+                _ => match word.parse::<f64>() {
+                    Ok(number) => Ok((i, Json::Number(number))),
+                    Err(_) => Err(format!("unexpected '{word}' at offset {start}")),
+                },
+            };
+        }
+    }
+}
+
+// JSON Parser
+// Takes JSON text and returns the parsed Json value, or an error message if the text is not valid JSON
+pub fn json_parser(input: &str) -> Result<Json, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let (i, value) = value_parser(&chars, 0)?;
+    if skip_whitespace(&chars, i) != chars.len() {
+        return Err(format!("unexpected characters at offset {i}"));
+    }
+    return Ok(value);
+}
@@ -0,0 +1,304 @@
+// Linter for DA programs
+// Runs after the semantic checker and looks for code that is valid but probably not what was meant
+// Every lint has a stable code and a name, either of which '-W', '-A' and '-D' accept, and warns by default
+
+use crate::ast::{identifiers, output_identifiers, Location, OutputOp, PrintItem, Program, Source};
+use crate::diagnostics::{diagnostic_reporter, Diagnostic, MessageFormat, Suggestion};
+use std::collections::HashMap;
+
+// Lints table stores the code, the name and a description of every lint
+// Codes are never reused, so a lint that is removed leaves a gap
+pub const LINTS: [(&str, &str, &str); 7] = [
+    (
+        "W001",
+        "unused_data",
+        "a data entry that nothing gives a value and nothing uses",
+    ),
+    (
+        "W002",
+        "unused_result",
+        "a value computed in the process section that is never printed or used",
+    ),
+    (
+        "W003",
+        "duplicate_read",
+        "the same CSV column read into two variables",
+    ),
+    (
+        "W004",
+        "duplicate_label",
+        "an output string that repeats an earlier one",
+    ),
+    (
+        "W005",
+        "shadowed_declaration",
+        "a function parameter with the name of a data entry or constant",
+    ),
+    (
+        "W006",
+        "legacy_declaration",
+        "a data entry declared with '=' instead of ':', which only the 2023 edition accepts",
+    ),
+    (
+        "W007",
+        "unused_read",
+        "a data entry read in the input section that nothing uses",
+    ),
+];
+
+// Level enum will be used to store what is done when a lint fires: nothing, a warning, or an error
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// Levels type stores the level of every lint that is not at its default, by code
+pub type Levels = HashMap<&'static str, Level>;
+
+// Warning struct stores one lint that fired: its code, where, what was found, and the text that replaces the token
+// there to fix it, if there is a fix that is certain to be right
+pub struct Warning {
+    pub code: &'static str,
+    pub location: Location,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+// Helper function
+// Takes the code or the name of a lint, or 'warnings' for all of them, as given to '-W', '-A' or '-D'
+// Returns the codes it stands for
+// Function panics if there is no such lint
+pub fn lint_resolver(lint: &str) -> Vec<&'static str> {
+    if lint == "warnings" {
+        return LINTS.iter().map(|(code, _, _)| *code).collect();
+    }
+    match LINTS
+        .iter()
+        .find(|(code, name, _)| code.eq_ignore_ascii_case(lint) || *name == lint)
+    {
+        Some((code, _, _)) => return vec![code],
+        None => panic!("\n\n; Unknown lint '{}'!\n\n", lint),
+    }
+}
+
+// Helper function
+// Takes a lint code and returns its name
+fn lint_name(code: &str) -> &'static str {
+    return LINTS
+        .iter()
+        .find(|(other, _, _)| *other == code)
+        .map_or("", |(_, name, _)| name);
+}
+
+// Helper function
+// Takes an output op and returns the strings it prints, as STRING lexemes
+fn output_strings(outputop: &OutputOp) -> Vec<&String> {
+    return match outputop {
+        OutputOp::Str(string) => vec![string],
+        OutputOp::Print(items) | OutputOp::Write { items, .. } => items
+            .iter()
+            .filter_map(|item| match item {
+                PrintItem::Str(string) => Some(string),
+                PrintItem::Id(_, _) => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+}
+
+// Linter
+// Takes a Program that passed the semantic checker
+// Returns the warnings of every lint, in the order of the lints and then of the source
+pub fn linter(program: &Program) -> Vec<Warning> {
+    let mut warnings: Vec<Warning> = Vec::new();
+
+    let mut used: Vec<String> = Vec::new();
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            identifiers(init, &mut used);
+        }
+    }
+    for processop in &program.processops {
+        identifiers(&processop.expr, &mut used);
+    }
+    for (outputop, _) in &program.outputops {
+        output_identifiers(outputop, &mut used);
+    }
+
+    // W001, W002 and W007: a value computed in the process section is reported where it is computed, a value read in
+    // the input section where it is read, and an entry that nothing gives a value where it is declared
+    // An entry that is initialised is not reported, since it may be kept for a later use of the value
+    // Constants are not reported, since their uses in the input section are resolved before the Program is built
+    for datadef in &program.datadefs {
+        if used.contains(&datadef.id) || datadef.init.is_some() {
+            continue;
+        }
+        if let Some(inputop) = program
+            .inputops
+            .iter()
+            .find(|inputop| inputop.id == datadef.id)
+        {
+            warnings.push(Warning {
+                code: "W007",
+                location: inputop.location.clone(),
+                message: format!("'{}' is read but never used.", datadef.id),
+                fix: None,
+            });
+            continue;
+        }
+        match program
+            .processops
+            .iter()
+            .find(|processop| processop.ids.contains(&datadef.id))
+        {
+            Some(processop) => warnings.push(Warning {
+                code: "W002",
+                location: processop.location.clone(),
+                message: format!("'{}' is computed but never printed or used.", datadef.id),
+                fix: None,
+            }),
+            None => warnings.push(Warning {
+                code: "W001",
+                location: datadef.location.clone(),
+                message: format!("'{}' is declared but never used.", datadef.id),
+                fix: None,
+            }),
+        }
+    }
+    warnings.sort_by_key(|warning| warning.code);
+
+    // W003: columns are compared as written, so a column given once by name and once by index is not found
+    for (i, inputop) in program.inputops.iter().enumerate() {
+        let Source::Csv {
+            file,
+            header,
+            column,
+            delimiter,
+        } = &inputop.source
+        else {
+            continue;
+        };
+        let earlier = program.inputops[..i]
+            .iter()
+            .find(|other| match &other.source {
+                Source::Csv {
+                    file: other_file,
+                    header: other_header,
+                    column: other_column,
+                    delimiter: other_delimiter,
+                } => {
+                    other_file == file
+                        && other_header == header
+                        && other_column == column
+                        && other_delimiter == delimiter
+                }
+                _ => false,
+            });
+        if let Some(earlier) = earlier {
+            warnings.push(Warning {
+                code: "W003",
+                location: inputop.location.clone(),
+                message: format!(
+                    "'{}' reads column {} of {}, which is already read into '{}'.",
+                    inputop.id, column, file, earlier.id
+                ),
+                fix: None,
+            });
+        }
+    }
+
+    // W004
+    let mut labels: Vec<&String> = Vec::new();
+    for (outputop, location) in &program.outputops {
+        for string in output_strings(outputop) {
+            if labels.contains(&string) {
+                warnings.push(Warning {
+                    code: "W004",
+                    location: location.clone(),
+                    message: format!("The label {} is already printed.", string),
+                    fix: None,
+                });
+            } else {
+                labels.push(string);
+            }
+        }
+    }
+
+    // W005
+    for define in &program.defines {
+        for param in &define.params {
+            let shadowed: &str = if program.datadefs.iter().any(|datadef| datadef.id == *param) {
+                "data entry"
+            } else if program.consts.iter().any(|constant| constant.id == *param) {
+                "constant"
+            } else {
+                continue;
+            };
+            warnings.push(Warning {
+                code: "W005",
+                location: define.location.clone(),
+                message: format!(
+                    "Parameter '{}' of '{}' shadows the {} '{}'.",
+                    param, define.name, shadowed, param
+                ),
+                fix: None,
+            });
+        }
+    }
+    return warnings;
+}
+
+// Warnings Reporter
+// Prints the warnings that are not allowed as diagnostics, and the denied ones as errors, and counts the warnings
+// Takes the warnings, the levels given with '-W', '-A' and '-D', and the format to print them in
+// Returns the number of warnings printed
+// Function panics if a denied lint fired, after printing every denied one
+pub fn warnings_reporter(warnings: &[Warning], levels: &Levels, format: MessageFormat) -> usize {
+    let mut printed: usize = 0;
+    let mut denied: usize = 0;
+    for warning in warnings {
+        let level: Level = levels.get(warning.code).copied().unwrap_or(Level::Warn);
+        let (severity, help): (&'static str, String) = match level {
+            Level::Allow => continue,
+            Level::Warn => (
+                "warning",
+                format!("'-A {}' allows this lint", lint_name(warning.code)),
+            ),
+            Level::Deny => (
+                "error",
+                format!("'-D {}' denies this lint", lint_name(warning.code)),
+            ),
+        };
+        let diagnostic: Diagnostic = Diagnostic {
+            severity,
+            code: Some(String::from(warning.code)),
+            location: Some(warning.location.clone()),
+            message: warning.message.clone(),
+            labels: Vec::new(),
+            helps: vec![help],
+            suggestions: warning
+                .fix
+                .iter()
+                .map(|replacement| Suggestion {
+                    location: warning.location.clone(),
+                    replacement: replacement.clone(),
+                    applicable: true,
+                })
+                .collect(),
+        };
+        diagnostic_reporter(&diagnostic, format);
+        if level == Level::Warn {
+            printed += 1;
+        } else {
+            denied += 1;
+        }
+    }
+    assert!(
+        denied == 0,
+        "\n\n; LINT ERROR!\n; Aborting because of {} denied lint(s).\n\n",
+        denied
+    );
+    return printed;
+}
@@ -0,0 +1,759 @@
+// Language server for DA programs
+// Speaks the Language Server Protocol over stdin and stdout, so editors get diagnostics, completion, hover,
+// go-to-definition and an outline of the sections of '.da' files
+// Messages are JSON-RPC bodies after a 'Content-Length' header, read and written with the json module
+// The lexer, parser and checker report errors by panicking, so every analysis runs under catch_unwind and the
+// panic message becomes the diagnostic
+
+use crate::ast::Location;
+use crate::checker::{builtin_signatures, multiple_results, semantic_checker, type_name};
+use crate::dataflow::dataflow_sorter;
+use crate::diagnostics::{diagnostic_parser, panic_message, suggestion_help, Diagnostic};
+use crate::interpreter::unescape;
+use crate::json::{json_parser, number, object, string, Json};
+use crate::{
+    comment_stripper, formatter, include_resolver, is_section_token, lexer, reserved_lexemes,
+    source_parser, token_end, Edition, Includes, Token, TokenTypes,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+// LSP symbol kinds for the outline and completion item kinds
+const SYMBOL_NAMESPACE: usize = 3;
+const SYMBOL_FIELD: usize = 8;
+const SYMBOL_FUNCTION: usize = 12;
+const SYMBOL_VARIABLE: usize = 13;
+const SYMBOL_CONSTANT: usize = 14;
+const SYMBOL_EVENT: usize = 24;
+const COMPLETION_FUNCTION: usize = 3;
+const COMPLETION_VARIABLE: usize = 6;
+const COMPLETION_KEYWORD: usize = 14;
+const COMPLETION_CONSTANT: usize = 21;
+
+// Section struct stores the header Token of a section and the Tokens of each of its items
+struct Section {
+    header: Token,
+    items: Vec<Vec<Token>>,
+}
+
+// Declaration struct stores a name given in a data or define section: its kind ('data', 'const' or 'define'), the
+// Token that declares it, and the declaring item as it is shown on hover
+struct Declaration {
+    kind: TokenTypes,
+    token: Token,
+    text: String,
+}
+
+// Helper function
+// Takes a line and a column, both counted from 1 like a Location, and returns an LSP position, counted from 0
+fn position(line: usize, column: usize) -> Json {
+    return object(vec![
+        ("line", number(line.saturating_sub(1))),
+        ("character", number(column.saturating_sub(1))),
+    ]);
+}
+
+// Helper function
+// Takes the first and the last Token of a range and returns the LSP range they span
+fn range(first: &Token, last: &Token) -> Json {
+    let (end_line, end_column) = token_end(last);
+    return object(vec![
+        (
+            "start",
+            position(first.location.line, first.location.column),
+        ),
+        ("end", position(end_line, end_column)),
+    ]);
+}
+
+// Helper function
+// Takes a file URI and returns the path it names, with percent-escapes decoded
+fn uri_path(uri: &str) -> PathBuf {
+    let encoded: &[u8] = uri.strip_prefix("file://").unwrap_or(uri).as_bytes();
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut i: usize = 0;
+    while i < encoded.len() {
+        let hex: Option<u8> = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if encoded[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    return PathBuf::from(String::from_utf8_lossy(&decoded).into_owned());
+}
+
+// Helper function
+// Takes a path and returns its file URI, with characters that are not allowed in a URI percent-escaped
+fn path_uri(path: &Path) -> String {
+    let path: PathBuf = fs::canonicalize(path).unwrap_or(path.to_path_buf());
+    let mut uri: String = String::from("file://");
+    for byte in path.display().to_string().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    return uri;
+}
+
+// Helper function
+// Takes the text of a document and the name of its file, and returns its Tokens, comments included
+// A document the lexer rejects has no Tokens
+fn tokens_reader(text: &str, file: &str) -> Vec<Token> {
+    return panic::catch_unwind(|| lexer(String::from(text), file)).unwrap_or_default();
+}
+
+// Sections Outliner
+// Splits Tokens into sections and the sections into items, the way the parser does, without requiring them to parse
+// Takes Tokens without comments
+// Returns the sections in the order they appear; include directives and 'end.' belong to no section
+fn sections_outliner(tokens: &[Token]) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut item: Vec<Token> = Vec::new();
+    let mut depth: usize = 0;
+    let mut assigned: bool = false;
+    let mut in_section: bool = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if is_section_token(token.token) {
+            if let Some(section) = sections.last_mut().filter(|_| !item.is_empty()) {
+                section.items.push(std::mem::take(&mut item));
+            }
+            item.clear();
+            depth = 0;
+            assigned = false;
+            in_section = token.token != TokenTypes::INCLUDE && token.token != TokenTypes::END;
+            if in_section {
+                sections.push(Section {
+                    header: token.clone(),
+                    items: Vec::new(),
+                });
+            }
+            continue;
+        }
+        // The ':' after a header and the file name after 'include' are not items
+        let after_header: bool = i > 0 && is_section_token(tokens[i - 1].token);
+        if !in_section || after_header {
+            continue;
+        }
+        match token.token {
+            TokenTypes::LPAREN | TokenTypes::LBRACKET => depth += 1,
+            TokenTypes::RPAREN | TokenTypes::RBRACKET => depth = depth.saturating_sub(1),
+            TokenTypes::ASSIGN if depth == 0 => assigned = true,
+            _ => {}
+        }
+        // A ',' at the top level ends an item, except between the identifiers of 'a, b, rsq = ...'
+        let process: bool = sections.last().unwrap().header.token == TokenTypes::PROCESS;
+        if token.token == TokenTypes::COMMA && depth == 0 && (!process || assigned) {
+            sections
+                .last_mut()
+                .unwrap()
+                .items
+                .push(std::mem::take(&mut item));
+            assigned = false;
+        } else {
+            item.push(token.clone());
+        }
+    }
+    if let Some(section) = sections.last_mut().filter(|_| !item.is_empty()) {
+        section.items.push(item);
+    }
+    return sections;
+}
+
+// Helper function
+// Takes the Tokens of an item and returns its text in the canonical layout of the formatter, on one line
+fn item_text(item: &[Token]) -> String {
+    let formatted: String = formatter::formatter(item);
+    let lines: Vec<&str> = formatted.lines().map(str::trim).collect();
+    return lines.join(" ");
+}
+
+// Declarations Collector
+// Collects the names declared in the data and define sections of a document and of the files it includes
+// Takes the Tokens of the document without comments, its path, the search path, the files visited so far, and the
+// list to add the Declarations to
+fn declarations_collector(
+    tokens: &[Token],
+    path: &Path,
+    search_path: &[PathBuf],
+    visited: &mut Vec<PathBuf>,
+    declarations: &mut Vec<Declaration>,
+) {
+    visited.push(fs::canonicalize(path).unwrap_or(path.to_path_buf()));
+    for section in sections_outliner(tokens) {
+        for item in &section.items {
+            let declared: Option<(TokenTypes, &Token)> =
+                match (section.header.token, item.as_slice()) {
+                    (TokenTypes::DATA, [constant, id, ..])
+                        if constant.token == TokenTypes::CONST =>
+                    {
+                        Some((TokenTypes::CONST, id))
+                    }
+                    (TokenTypes::DATA, [id, ..]) => Some((TokenTypes::DATA, id)),
+                    (TokenTypes::DEFINE, [name, ..]) => Some((TokenTypes::DEFINE, name)),
+                    _ => None,
+                };
+            if let Some((kind, token)) = declared.filter(|(_, token)| token.token == TokenTypes::ID)
+            {
+                declarations.push(Declaration {
+                    kind,
+                    token: token.clone(),
+                    text: item_text(item),
+                });
+            }
+        }
+    }
+
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        if token.token != TokenTypes::STRING || tokens[i - 1].token != TokenTypes::INCLUDE {
+            continue;
+        }
+        let included: Option<PathBuf> =
+            include_resolver(&unescape(&token.lexeme), path, search_path);
+        if let Some(included) = included {
+            let canonical: PathBuf = fs::canonicalize(&included).unwrap_or(included.clone());
+            if visited.contains(&canonical) {
+                continue;
+            }
+            if let Ok(text) = fs::read_to_string(&included) {
+                let file: String = included.display().to_string();
+                let included_tokens: Vec<Token> = comment_stripper(tokens_reader(&text, &file));
+                declarations_collector(
+                    &included_tokens,
+                    &included,
+                    search_path,
+                    visited,
+                    declarations,
+                );
+            }
+        }
+    }
+}
+
+// Helper function
+// Takes a Location and the Tokens of its file
+// Returns the LSP range of the Token at the Location, or of the character there if no Token starts there
+fn location_range(location: &Location, tokens: &[Token]) -> Json {
+    match tokens.iter().find(|token| token.location == *location) {
+        Some(token) => return range(token, token),
+        None => {
+            return object(vec![
+                ("start", position(location.line, location.column)),
+                ("end", position(location.line, location.column + 1)),
+            ])
+        }
+    }
+}
+
+// Diagnostics Generator
+// Lexes, parses and (for a whole program) checks a document, and turns the first error found into a diagnostic
+// Takes the text of the document, its path, and the search path
+// Returns the LSP diagnostics of the document, which are empty if it has no errors
+fn diagnostics_generator(text: &str, path: &Path, search_path: &[PathBuf]) -> Vec<Json> {
+    let file: String = path.display().to_string();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let tokens: Vec<Token> = comment_stripper(lexer(String::from(text), &file));
+        let mut includes: Includes = Includes {
+            search_path: search_path.to_vec(),
+            stack: vec![path.to_path_buf()],
+            included: Vec::new(),
+            edition: Edition::Edition2024,
+            warnings: Vec::new(),
+        };
+        // A file of sections for 'include' only makes sense with the program that includes it
+        let (mut program, whole) = source_parser(tokens, &file, &mut includes);
+        if whole {
+            dataflow_sorter(&mut program);
+            semantic_checker(&program);
+        }
+    }));
+    let payload = match result {
+        Ok(()) => return Vec::new(),
+        Err(payload) => payload,
+    };
+    let message: String = panic_message(&*payload);
+    // A panic that is not a diagnostic is a bug, and is shown as it is
+    let diagnostic: Diagnostic = diagnostic_parser(&message).unwrap_or(Diagnostic {
+        severity: "error",
+        code: None,
+        location: None,
+        message: String::from(message.trim()),
+        labels: Vec::new(),
+        helps: Vec::new(),
+        suggestions: Vec::new(),
+    });
+    let mut detail: String = diagnostic.message.clone();
+    let tokens: Vec<Token> = tokens_reader(text, &file);
+    let error_range: Json = match &diagnostic.location {
+        Some(location) if location.file == file => location_range(location, &tokens),
+        // Errors in included files and errors without a location are shown at the end of the document
+        location => {
+            if let Some(location) = location {
+                detail = format!("{location}: {detail}");
+            }
+            match tokens.last() {
+                Some(last) => range(last, last),
+                None => object(vec![("start", position(1, 1)), ("end", position(1, 1))]),
+            }
+        }
+    };
+    let source = |name: &str| -> Option<String> {
+        if name == file {
+            return Some(String::from(text));
+        }
+        return fs::read_to_string(name).ok();
+    };
+    for suggestion in &diagnostic.suggestions {
+        let help: String = suggestion_help(&diagnostic, suggestion, &source);
+        detail.push_str(&format!("\nhelp: {help}"));
+    }
+    for help in &diagnostic.helps {
+        detail.push_str(&format!("\nhelp: {help}"));
+    }
+    // Labels in this document point into it, labels in included files have their own tokens
+    let related: Vec<Json> = diagnostic
+        .labels
+        .iter()
+        .map(|(location, label)| {
+            let label_tokens: Vec<Token> = if location.file == file {
+                tokens.clone()
+            } else {
+                tokens_reader(
+                    &fs::read_to_string(&location.file).unwrap_or_default(),
+                    &location.file,
+                )
+            };
+            object(vec![
+                (
+                    "location",
+                    object(vec![
+                        ("uri", string(&path_uri(Path::new(&location.file)))),
+                        ("range", location_range(location, &label_tokens)),
+                    ]),
+                ),
+                ("message", string(label)),
+            ])
+        })
+        .collect();
+    return vec![object(vec![
+        ("range", error_range),
+        ("severity", number(1)),
+        ("source", string("da")),
+        (
+            "code",
+            diagnostic.code.as_deref().map_or(Json::Null, string),
+        ),
+        ("message", string(&detail)),
+        ("relatedInformation", Json::Array(related)),
+    ])];
+}
+
+// Helper function
+// Takes the Tokens of a document and an LSP position, and returns the Token under the position, if any
+// A position right after a Token counts as being on it, which is where the cursor is while typing a name
+fn token_finder<'a>(tokens: &'a [Token], params: &Json) -> Option<&'a Token> {
+    let position: &Json = params.get("position")?;
+    let (Some(Json::Number(line)), Some(Json::Number(character))) =
+        (position.get("line"), position.get("character"))
+    else {
+        return None;
+    };
+    let line: usize = *line as usize + 1;
+    let column: usize = *character as usize + 1;
+    let on_line = |token: &&Token| {
+        token.location.line == line
+            && token.location.column <= column
+            && token.token != TokenTypes::COMMENT
+    };
+    return tokens
+        .iter()
+        .filter(on_line)
+        .find(|token| column < token_end(token).1)
+        .or_else(|| {
+            tokens
+                .iter()
+                .filter(on_line)
+                .find(|token| column == token_end(token).1)
+        });
+}
+
+// Helper function
+// Takes the name of a statistics function and returns its signature, e.g. 'mean(vector) -> number', one line for
+// each way it can be called
+fn signature_text(name: &str) -> Option<String> {
+    let (params, result) = builtin_signatures().get(name)?.clone();
+    let params: Vec<&str> = params.iter().map(|param| type_name(*param)).collect();
+    let mut text: String = format!("{name}({}) -> {}", params.join(", "), type_name(result));
+    let vectors: [crate::ast::DataType; 2] = [crate::ast::DataType::Vector; 2];
+    if let Some(results) = multiple_results(name, &vectors) {
+        let results: Vec<&str> = results.iter().map(|result| type_name(*result)).collect();
+        text.push_str(&format!(
+            "\n{name}(vector, vector) -> {}",
+            results.join(", ")
+        ));
+    }
+    return Some(text);
+}
+
+// Helper function
+// Takes the Tokens of a document and a Token in it, and returns the parameter Token of the user function the Token
+// is in the body of, if the Token is one of its parameters
+fn parameter_finder(tokens: &[Token], token: &Token) -> Option<Token> {
+    let section: Section = sections_outliner(tokens)
+        .into_iter()
+        .find(|section| section.header.token == TokenTypes::DEFINE)?;
+    let item: &Vec<Token> = section
+        .items
+        .iter()
+        .find(|item| item.iter().any(|other| other.location == token.location))?;
+    let params_end: usize = item
+        .iter()
+        .position(|other| other.token == TokenTypes::RPAREN)?;
+    return item[..params_end]
+        .iter()
+        .skip(1)
+        .find(|param| param.token == TokenTypes::ID && param.lexeme == token.lexeme)
+        .cloned();
+}
+
+// Hover Generator
+// Takes the Tokens of a document, its Declarations, and the request parameters
+// Returns the hover for the Token under the cursor: the declaration of an identifier or the signature of a
+// statistics function
+fn hover_generator(tokens: &[Token], declarations: &[Declaration], params: &Json) -> Json {
+    let token: &Token = match token_finder(tokens, params) {
+        Some(token) => token,
+        None => return Json::Null,
+    };
+    let text: String = if let Some(signature) = signature_text(&token.lexeme) {
+        signature
+    } else if token.token != TokenTypes::ID {
+        return Json::Null;
+    } else if let Some(param) = parameter_finder(tokens, token) {
+        format!("{} (parameter)", param.lexeme)
+    } else {
+        match declarations
+            .iter()
+            .find(|declaration| declaration.token.lexeme == token.lexeme)
+        {
+            Some(declaration) => declaration.text.clone(),
+            None => return Json::Null,
+        }
+    };
+    return object(vec![
+        (
+            "contents",
+            object(vec![
+                ("kind", string("markdown")),
+                ("value", string(&format!("```da\n{text}\n```"))),
+            ]),
+        ),
+        ("range", range(token, token)),
+    ]);
+}
+
+// Definition Generator
+// Takes the Tokens of a document, its Declarations, and the request parameters
+// Returns the LSP location of the declaration of the identifier under the cursor: its 'data:' entry, its constant,
+// its user function, or the parameter it names
+fn definition_generator(tokens: &[Token], declarations: &[Declaration], params: &Json) -> Json {
+    let token: &Token =
+        match token_finder(tokens, params).filter(|token| token.token == TokenTypes::ID) {
+            Some(token) => token,
+            None => return Json::Null,
+        };
+    let target: Token = match parameter_finder(tokens, token) {
+        Some(param) => param,
+        None => match declarations
+            .iter()
+            .find(|declaration| declaration.token.lexeme == token.lexeme)
+        {
+            Some(declaration) => declaration.token.clone(),
+            None => return Json::Null,
+        },
+    };
+    return object(vec![
+        ("uri", string(&path_uri(Path::new(&target.location.file)))),
+        ("range", range(&target, &target)),
+    ]);
+}
+
+// Completion Generator
+// Takes the Declarations of a document
+// Returns completion items for the declared identifiers and user functions, the statistics functions, and the
+// keywords, sorted by label
+fn completion_generator(declarations: &[Declaration]) -> Json {
+    let mut items: Vec<(String, usize, String)> = Vec::new();
+    for declaration in declarations {
+        let kind: usize = match declaration.kind {
+            TokenTypes::CONST => COMPLETION_CONSTANT,
+            TokenTypes::DEFINE => COMPLETION_FUNCTION,
+            _ => COMPLETION_VARIABLE,
+        };
+        if !items
+            .iter()
+            .any(|(label, _, _)| *label == declaration.token.lexeme)
+        {
+            items.push((
+                declaration.token.lexeme.clone(),
+                kind,
+                declaration.text.clone(),
+            ));
+        }
+    }
+    for name in builtin_signatures().keys() {
+        items.push((
+            String::from(*name),
+            COMPLETION_FUNCTION,
+            signature_text(name).unwrap_or_default(),
+        ));
+    }
+    for name in reserved_lexemes().keys() {
+        if !builtin_signatures().contains_key(name) {
+            items.push((String::from(*name), COMPLETION_KEYWORD, String::new()));
+        }
+    }
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let items: Vec<Json> = items
+        .into_iter()
+        .map(|(label, kind, detail)| {
+            let mut members: Vec<(&str, Json)> =
+                vec![("label", string(&label)), ("kind", number(kind))];
+            if !detail.is_empty() {
+                members.push(("detail", string(&detail)));
+            }
+            return object(members);
+        })
+        .collect();
+    return Json::Array(items);
+}
+
+// Symbols Generator
+// Takes the Tokens of a document without comments
+// Returns one LSP document symbol per section, holding one symbol per item: the entries of 'data:', the functions
+// of 'define:', the identifiers given a value in 'input:' and 'process:', and the statements of 'output:'
+fn symbols_generator(tokens: &[Token]) -> Json {
+    let mut symbols: Vec<Json> = Vec::new();
+    for section in sections_outliner(tokens) {
+        let mut children: Vec<Json> = Vec::new();
+        for item in &section.items {
+            let text: String = item_text(item);
+            let ids: Vec<&str> = item
+                .iter()
+                .take_while(|token| token.token != TokenTypes::ASSIGN)
+                .filter(|token| token.token == TokenTypes::ID)
+                .map(|token| token.lexeme.as_str())
+                .collect();
+            let (name, kind): (String, usize) = match section.header.token {
+                TokenTypes::DATA if item[0].token == TokenTypes::CONST => {
+                    (ids.join(""), SYMBOL_CONSTANT)
+                }
+                TokenTypes::DATA => (item[0].lexeme.clone(), SYMBOL_VARIABLE),
+                TokenTypes::DEFINE => (item[0].lexeme.clone(), SYMBOL_FUNCTION),
+                TokenTypes::INPUT | TokenTypes::PROCESS => (ids.join(", "), SYMBOL_FIELD),
+                _ => (text.clone(), SYMBOL_EVENT),
+            };
+            let name_token: &Token = item
+                .iter()
+                .find(|token| token.token == TokenTypes::ID)
+                .unwrap_or(&item[0]);
+            children.push(object(vec![
+                ("name", string(&name)),
+                ("detail", string(&text)),
+                ("kind", number(kind)),
+                ("range", range(&item[0], item.last().unwrap())),
+                ("selectionRange", range(name_token, name_token)),
+            ]));
+        }
+        let last: &Token = match section.items.last() {
+            Some(item) => item.last().unwrap(),
+            None => &section.header,
+        };
+        symbols.push(object(vec![
+            ("name", string(&section.header.lexeme)),
+            ("kind", number(SYMBOL_NAMESPACE)),
+            ("range", range(&section.header, last)),
+            ("selectionRange", range(&section.header, &section.header)),
+            ("children", Json::Array(children)),
+        ]));
+    }
+    return Json::Array(symbols);
+}
+
+// Helper function
+// Reads one message from the editor
+// Takes the reader for stdin
+// Returns the body of the message, or None once stdin is closed
+fn message_reader(reader: &mut impl BufRead) -> Option<String> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header: String = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header: &str = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+    let mut body: Vec<u8> = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+    return String::from_utf8(body).ok();
+}
+
+// Helper function
+// Takes a JSON-RPC message and sends it to the editor
+fn message_writer(message: Json) {
+    let body: String = message.to_string();
+    let mut stdout = std::io::stdout().lock();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = stdout.flush();
+}
+
+// Helper function
+// Takes the URI of a document, its text, and the search path, and sends the editor the document's diagnostics
+fn diagnostics_publisher(uri: &str, text: &str, search_path: &[PathBuf]) {
+    let diagnostics: Vec<Json> = diagnostics_generator(text, &uri_path(uri), search_path);
+    message_writer(object(vec![
+        ("jsonrpc", string("2.0")),
+        ("method", string("textDocument/publishDiagnostics")),
+        (
+            "params",
+            object(vec![
+                ("uri", string(uri)),
+                ("diagnostics", Json::Array(diagnostics)),
+            ]),
+        ),
+    ]));
+}
+
+// Server
+// Answers the requests and notifications of an editor until it sends 'exit' or closes stdin
+// Documents are synchronized in full on every change, and their diagnostics are published after every change
+// Takes the search path for included files
+pub fn server(search_path: Vec<PathBuf>) {
+    // Errors in documents are expected and turned into diagnostics, so their panic messages are not printed
+    panic::set_hook(Box::new(|_| {}));
+    let mut reader = std::io::stdin().lock();
+    let mut documents: HashMap<String, String> = HashMap::new();
+
+    while let Some(body) = message_reader(&mut reader) {
+        let message: Json = match json_parser(&body) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+        let method: String = match message.get("method") {
+            Some(Json::String(method)) => method.clone(),
+            _ => continue,
+        };
+        let params: Json = message.get("params").cloned().unwrap_or(Json::Null);
+        let uri: String = match params
+            .get("textDocument")
+            .and_then(|document| document.get("uri"))
+        {
+            Some(Json::String(uri)) => uri.clone(),
+            _ => String::new(),
+        };
+        let text: String = documents.get(&uri).cloned().unwrap_or_default();
+        let file: String = uri_path(&uri).display().to_string();
+        let tokens: Vec<Token> = comment_stripper(tokens_reader(&text, &file));
+        let mut declarations: Vec<Declaration> = Vec::new();
+        if method == "textDocument/hover"
+            || method == "textDocument/definition"
+            || method == "textDocument/completion"
+        {
+            declarations_collector(
+                &tokens,
+                &uri_path(&uri),
+                &search_path,
+                &mut Vec::new(),
+                &mut declarations,
+            );
+        }
+
+        let result: Result<Json, (i32, &str)> = match method.as_str() {
+            "initialize" => Ok(object(vec![
+                (
+                    "capabilities",
+                    object(vec![
+                        ("textDocumentSync", number(1)),
+                        ("completionProvider", object(Vec::new())),
+                        ("hoverProvider", Json::Bool(true)),
+                        ("definitionProvider", Json::Bool(true)),
+                        ("documentSymbolProvider", Json::Bool(true)),
+                    ]),
+                ),
+                ("serverInfo", object(vec![("name", string("da"))])),
+            ])),
+            "shutdown" => Ok(Json::Null),
+            "exit" => return,
+            "textDocument/didOpen" | "textDocument/didChange" => {
+                let text: Option<&Json> = match params.get("contentChanges") {
+                    Some(Json::Array(changes)) => {
+                        changes.last().and_then(|change| change.get("text"))
+                    }
+                    _ => params
+                        .get("textDocument")
+                        .and_then(|document| document.get("text")),
+                };
+                if let Some(Json::String(text)) = text {
+                    documents.insert(uri.clone(), text.clone());
+                    diagnostics_publisher(&uri, text, &search_path);
+                }
+                continue;
+            }
+            "textDocument/didSave" => {
+                // Included files may have changed on disk
+                diagnostics_publisher(&uri, &text, &search_path);
+                continue;
+            }
+            "textDocument/didClose" => {
+                documents.remove(&uri);
+                diagnostics_publisher(&uri, "", &search_path);
+                continue;
+            }
+            "textDocument/hover" => Ok(hover_generator(&tokens, &declarations, &params)),
+            "textDocument/definition" => Ok(definition_generator(&tokens, &declarations, &params)),
+            "textDocument/completion" => Ok(completion_generator(&declarations)),
+            "textDocument/documentSymbol" => Ok(symbols_generator(&tokens)),
+            _ => Err((-32601, "Method not found")),
+        };
+
+        // Notifications have no id and get no response
+        if let Some(id) = message.get("id") {
+            let outcome: (&str, Json) = match result {
+                Ok(result) => ("result", result),
+                Err((code, text)) => (
+                    "error",
+                    object(vec![
+                        ("code", Json::Number(code as f64)),
+                        ("message", string(text)),
+                    ]),
+                ),
+            };
+            message_writer(object(vec![
+                ("jsonrpc", string("2.0")),
+                ("id", id.clone()),
+                outcome,
+            ]));
+        }
+    }
+}
@@ -3,47 +3,144 @@
 
 // The variable 'i' will be used throughout this source code as an integer iterator
 
+// Functions return their results with an explicit 'return'
+#![allow(clippy::needless_return)]
+
+mod ast;
+mod checker;
+mod dataflow;
+mod diagnostics;
+mod dump;
+mod fixer;
+mod formatter;
+mod interpreter;
+mod json;
+mod linter;
+mod lsp;
+mod optimizer;
+mod prolog;
+mod repl;
+mod scheme;
+mod svg;
+
+use ast::{
+    Column, Const, DataDef, DataType, Define, Expr, InputOp, Location, OutputOp, PrintItem,
+    ProcessOp, Program, Source,
+};
 use core::cmp::PartialEq;
+use diagnostics::{closest_match, MessageFormat};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
+use std::path::{Path, PathBuf};
 
-// Flag enum will be used to indicate whether Prolog or Scheme output is requested
+// Flag enum will be used to indicate whether Prolog or Scheme output, running the program, formatting it, linting
+// it, dumping its Tokens or its AST, running the language server, or the REPL is requested
 #[derive(PartialEq, Eq)]
 enum Flag {
     Scheme,
     Prolog,
+    Run,
+    Format,
+    Fix,
+    Lint,
+    Tokens,
+    Ast,
+    Server,
+    Repl,
     None,
 }
 
 // TokenTypes enum will be used to store a token type and to compare tokens
-#[derive(Clone, Copy, PartialEq, Eq)]
+// Token names are written in capitals, like the terminals of the grammar
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum TokenTypes {
     DATA,
+    CONST,
+    DEFINE,
     INPUT,
     PROCESS,
     OUTPUT,
     END,
+    INCLUDE,
+    EOF,
     ID,
     NUM,
     TRUE,
     FALSE,
     READ,
+    READJSON,
+    READLINES,
+    READTABLE,
     COLON,
     COMMA,
     PERIOD,
     LPAREN,
     RPAREN,
+    LBRACKET,
+    RBRACKET,
     ASSIGN,
+    PLUS,
+    MINUS,
+    TIMES,
+    DIVIDE,
+    LESS,
+    GREATER,
+    LESSEQUAL,
+    GREATEREQUAL,
+    EQUAL,
+    NOTEQUAL,
     VECTOR,
     NUMBER,
+    TABLE,
+    REGRESSION,
     REGRESSIONA,
     REGRESSIONB,
     MEAN,
     STDDEV,
     CORRELATION,
+    PRINT,
+    WRITE,
+    APPEND,
+    PLOT,
+    HISTOGRAM,
+    FILTER,
+    ABS,
+    LOG,
+    SLICE,
+    CONCAT,
     STRING,
+    COMMENT,
+}
+
+// DataEntry enum will be used to store one entry of the data section: a declaration or a constant
+enum DataEntry {
+    Def(DataDef),
+    Const(Const),
+}
+
+// Edition enum will be used to store the edition of the language a program is written in, given with '--edition'
+// Grammar changes are gated on the edition, so that older programs keep parsing: the 2023 edition still accepts
+// data entries declared with '=' ('x = vector'), with a warning
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edition {
+    Edition2023,
+    Edition2024,
+}
+
+// Includes struct stores what the parser needs to splice in included files: the directories given with '-I',
+// the files being parsed (the main file first, the innermost included file last), and the files already included
+// It also stores the edition the files are written in and the warnings about the deprecated syntax found in them
+struct Includes {
+    search_path: Vec<PathBuf>,
+    stack: Vec<PathBuf>,
+    included: Vec<PathBuf>,
+    edition: Edition,
+    warnings: Vec<linter::Warning>,
 }
 
 // Token struct will be used to store each token in a vector
@@ -51,26 +148,17 @@ enum TokenTypes {
 struct Token {
     token: TokenTypes,
     lexeme: String,
+    location: Location,
 }
 
-// Lexer function
-// Takes String input and produces vector of Tokens
-// If lexical or syntax errors are found, function panics
-fn lexer(input: String) -> Vec<Token> {
-    let mut i: usize = 0;
-    let input_length: usize = input.len();
-    let mut curr_char: char;
-    let mut output: Vec<Token> = Vec::new();
-    let special_lexeme: HashMap<char, TokenTypes> = HashMap::from([
-        (':', TokenTypes::COLON),
-        (',', TokenTypes::COMMA),
-        ('.', TokenTypes::PERIOD),
-        ('(', TokenTypes::LPAREN),
-        (')', TokenTypes::RPAREN),
-        ('=', TokenTypes::ASSIGN),
-    ]);
-    let reserved_lexeme: HashMap<&str, TokenTypes> = HashMap::from([
+// Helper function
+// Returns the reserved words of the language and their TokenTypes
+fn reserved_lexemes() -> HashMap<&'static str, TokenTypes> {
+    return HashMap::from([
         ("data", TokenTypes::DATA),
+        ("const", TokenTypes::CONST),
+        ("define", TokenTypes::DEFINE),
+        ("include", TokenTypes::INCLUDE),
         ("input", TokenTypes::INPUT),
         ("process", TokenTypes::PROCESS),
         ("output", TokenTypes::OUTPUT),
@@ -78,22 +166,89 @@ fn lexer(input: String) -> Vec<Token> {
         ("true", TokenTypes::TRUE),
         ("false", TokenTypes::FALSE),
         ("read", TokenTypes::READ),
+        ("read_json", TokenTypes::READJSON),
+        ("read_lines", TokenTypes::READLINES),
+        ("read_table", TokenTypes::READTABLE),
         ("vector", TokenTypes::VECTOR),
         ("number", TokenTypes::NUMBER),
+        ("table", TokenTypes::TABLE),
+        ("regression", TokenTypes::REGRESSION),
         ("regressiona", TokenTypes::REGRESSIONA),
         ("regressionb", TokenTypes::REGRESSIONB),
         ("mean", TokenTypes::MEAN),
         ("stddev", TokenTypes::STDDEV),
         ("correlation", TokenTypes::CORRELATION),
+        ("print", TokenTypes::PRINT),
+        ("write", TokenTypes::WRITE),
+        ("append", TokenTypes::APPEND),
+        ("plot", TokenTypes::PLOT),
+        ("histogram", TokenTypes::HISTOGRAM),
+        ("filter", TokenTypes::FILTER),
+        ("abs", TokenTypes::ABS),
+        ("log", TokenTypes::LOG),
+        ("slice", TokenTypes::SLICE),
+        ("concat", TokenTypes::CONCAT),
     ]);
+}
 
-    while i < input_length {
-        curr_char = input.chars().nth(i).unwrap();
+// Lexer function
+// Takes String input and the name of the file it was read from, and produces vector of Tokens
+// Each Token records the file, line, and column it starts at, and comments starting with '#' become COMMENT Tokens
+// If lexical or syntax errors are found, function panics
+fn lexer(input: String, file: &str) -> Vec<Token> {
+    let mut i: usize = 0;
+    let mut line: usize = 1;
+    let mut line_start: usize = 0;
+    // The input is indexed by character, since comments and strings may hold any of them
+    let chars: Vec<char> = input.chars().collect();
+    let input_length: usize = chars.len();
+    let mut curr_char: char;
+    let mut output: Vec<Token> = Vec::new();
+    let special_lexeme: HashMap<char, TokenTypes> = HashMap::from([
+        (':', TokenTypes::COLON),
+        (',', TokenTypes::COMMA),
+        ('.', TokenTypes::PERIOD),
+        ('(', TokenTypes::LPAREN),
+        (')', TokenTypes::RPAREN),
+        ('[', TokenTypes::LBRACKET),
+        (']', TokenTypes::RBRACKET),
+        ('=', TokenTypes::ASSIGN),
+        ('+', TokenTypes::PLUS),
+        ('-', TokenTypes::MINUS),
+        ('*', TokenTypes::TIMES),
+        ('/', TokenTypes::DIVIDE),
+        ('<', TokenTypes::LESS),
+        ('>', TokenTypes::GREATER),
+    ]);
+    let double_lexeme: HashMap<&str, TokenTypes> = HashMap::from([
+        ("<=", TokenTypes::LESSEQUAL),
+        (">=", TokenTypes::GREATEREQUAL),
+        ("==", TokenTypes::EQUAL),
+        ("!=", TokenTypes::NOTEQUAL),
+    ]);
+    let reserved_lexeme: HashMap<&str, TokenTypes> = reserved_lexemes();
 
-        if special_lexeme.contains_key(&curr_char) {
+    while i < input_length {
+        curr_char = chars[i];
+        let pair: String = chars[i..].iter().take(2).collect();
+        let location: Location = Location {
+            file: String::from(file),
+            line,
+            column: i - line_start + 1,
+        };
+
+        if double_lexeme.contains_key(pair.as_str()) {
+            output.push(Token {
+                token: double_lexeme[pair.as_str()],
+                lexeme: pair,
+                location: location.clone(),
+            });
+            i += 2;
+        } else if special_lexeme.contains_key(&curr_char) {
             output.push(Token {
                 token: special_lexeme[&curr_char],
                 lexeme: String::from(curr_char),
+                location: location.clone(),
             });
             i += 1;
         } else if curr_char.is_ascii_lowercase() {
@@ -101,8 +256,8 @@ fn lexer(input: String) -> Vec<Token> {
             lexeme.push(curr_char);
             i += 1;
             while i < input_length {
-                curr_char = input.chars().nth(i).unwrap();
-                if curr_char.is_ascii_lowercase() {
+                curr_char = chars[i];
+                if curr_char.is_ascii_lowercase() || curr_char == '_' {
                     lexeme.push(curr_char);
                     i += 1;
                 } else {
@@ -112,12 +267,14 @@ fn lexer(input: String) -> Vec<Token> {
             if reserved_lexeme.contains_key(lexeme.as_str()) {
                 output.push(Token {
                     token: reserved_lexeme[lexeme.as_str()],
-                    lexeme: lexeme,
+                    lexeme,
+                    location: location.clone(),
                 });
             } else {
                 output.push(Token {
                     token: TokenTypes::ID,
-                    lexeme: lexeme,
+                    lexeme,
+                    location: location.clone(),
                 });
             }
         } else if curr_char.is_ascii_digit() {
@@ -125,53 +282,107 @@ fn lexer(input: String) -> Vec<Token> {
             lexeme.push(curr_char);
             i += 1;
             while i < input_length {
-                curr_char = input.chars().nth(i).unwrap();
+                curr_char = chars[i];
                 if curr_char.is_ascii_digit() {
                     lexeme.push(curr_char);
                     i += 1;
+                } else if curr_char == '.'
+                    && !lexeme.contains('.')
+                    && i + 1 < input_length
+                    && chars[i + 1].is_ascii_digit()
+                {
+                    // A period followed by a digit continues the number as its fractional part
+                    lexeme.push(curr_char);
+                    i += 1;
                 } else {
                     break;
                 }
             }
             output.push(Token {
                 token: TokenTypes::NUM,
-                lexeme: lexeme,
+                lexeme,
+                location: location.clone(),
             });
         } else if curr_char == '\"' {
             let mut lexeme = String::new();
             lexeme.push(curr_char);
             i += 1;
             while i < input_length {
-                curr_char = input.chars().nth(i).unwrap();
-                if curr_char.is_ascii_lowercase()
-                    || curr_char.is_ascii_whitespace()
-                    || curr_char.is_ascii_digit()
-                    || curr_char == '.'
-                    || curr_char == '='
-                {
+                curr_char = chars[i];
+                if curr_char == '\"' {
                     lexeme.push(curr_char);
                     i += 1;
-                } else if curr_char == '\"' {
+                    break;
+                } else if curr_char == '\\' && i + 1 < input_length {
+                    // Escape sequences such as "\t" or "\"" are kept verbatim for the backends
+                    lexeme.push(curr_char);
+                    lexeme.push(chars[i + 1]);
+                    i += 2;
+                } else if curr_char.is_ascii_graphic() || curr_char.is_ascii_whitespace() {
+                    // Header names in CSV files may contain capitals, underscores, dashes, etc.
+                    if curr_char == '\n' {
+                        line += 1;
+                        line_start = i + 1;
+                    }
                     lexeme.push(curr_char);
                     i += 1;
-                    break;
                 } else {
                     panic!(
-                        "\n\n; SYNTAX ERROR!\n; Expected '\"' after '{}'.\n\n",
-                        lexeme
+                        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected '\"' after '{}'.\n\n",
+                        location, lexeme
                     );
                 }
             }
             output.push(Token {
                 token: TokenTypes::STRING,
-                lexeme: lexeme,
+                lexeme,
+                location: location.clone(),
+            });
+        } else if curr_char == '#' {
+            // Comments run to the end of the line and are kept as tokens for the formatter
+            let mut lexeme = String::new();
+            while i < input_length {
+                curr_char = chars[i];
+                if curr_char == '\n' {
+                    break;
+                }
+                lexeme.push(curr_char);
+                i += 1;
+            }
+            output.push(Token {
+                token: TokenTypes::COMMENT,
+                lexeme: String::from(lexeme.trim_end()),
+                location: location.clone(),
             });
         } else if curr_char.is_ascii_whitespace() {
+            if curr_char == '\n' {
+                line += 1;
+                line_start = i + 1;
+            }
             i += 1;
+        } else if curr_char.is_ascii_uppercase() {
+            // A capitalised word, e.g. 'Vector', is a keyword or an identifier written with the wrong case
+            let word: String = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphabetic() || **c == '_')
+                .collect::<String>()
+                .to_ascii_lowercase();
+            // Lowercasing it is certain to be right, unless it is also misspelled, e.g. 'Vectr'
+            let replacement: &str =
+                closest_match(&word, reserved_lexeme.keys().copied()).unwrap_or(&word);
+            let kind: &str = if replacement == word {
+                "fix"
+            } else {
+                "suggestion"
+            };
+            panic!(
+                "\n\n; LEXICAL ERROR[E0001]!\n; {}: Unrecognized character '{}'.\n; {}: {}: {}\n; help: keywords and identifiers are written in lowercase letters\n\n",
+                location, curr_char, location, kind, replacement
+            );
         } else {
             panic!(
-                "\n\n; LEXICAL ERROR!\n; Unrecognized character '{}'.\n\n",
-                curr_char
+                "\n\n; LEXICAL ERROR[E0001]!\n; {}: Unrecognized character '{}'.\n\n",
+                location, curr_char
             );
         }
     }
@@ -179,193 +390,398 @@ fn lexer(input: String) -> Vec<Token> {
     return output;
 }
 
+// Helper function
+// Takes a vector of Tokens and returns it without its COMMENT Tokens, which the parser does not see
+fn comment_stripper(tokens: Vec<Token>) -> Vec<Token> {
+    return tokens
+        .into_iter()
+        .filter(|token| token.token != TokenTypes::COMMENT)
+        .collect();
+}
+
+// Helper function
+// Takes a vector of Tokens and the name of the file they were read from, and ends them with an EOF Token
+// Included files hold sections only, so their tokens end with an EOF token instead of 'end.'
+fn eof_appender(tokens: &mut Vec<Token>, file: &str) {
+    let eof_location: Location = match tokens.last() {
+        Some(last) => last.location.clone(),
+        None => Location {
+            file: String::from(file),
+            line: 1,
+            column: 1,
+        },
+    };
+    tokens.push(Token {
+        token: TokenTypes::EOF,
+        lexeme: String::from("end of file"),
+        location: eof_location,
+    });
+}
+
 // Helper function
 // Takes an unsigened integer and a vector of Tokens and returns the Token in the vector at the index of the integer
-fn get_next_token(index: usize, tokens: &Vec<Token>) -> Token {
+fn get_next_token(index: usize, tokens: &[Token]) -> Token {
     return tokens[index].clone();
 }
 
+// Helper function
+// Takes a Token and returns the line and column just after it
+fn token_end(token: &Token) -> (usize, usize) {
+    let newlines: usize = token.lexeme.matches('\n').count();
+    if newlines == 0 {
+        return (
+            token.location.line,
+            token.location.column + token.lexeme.chars().count(),
+        );
+    }
+    let last_line: &str = token.lexeme.rsplit('\n').next().unwrap_or("");
+    return (
+        token.location.line + newlines,
+        last_line.chars().count() + 1,
+    );
+}
+
+// Helper function
+// Takes a STRING lexeme (quotes included) and returns whether it holds exactly one character
+// An escape sequence such as "\t" counts as a single character
+fn is_single_char_string(lexeme: &str) -> bool {
+    let contents: Vec<char> = lexeme[1..lexeme.len() - 1].chars().collect();
+    return contents.len() == 1 || (contents.len() == 2 && contents[0] == '\\');
+}
+
+// Helper function
+// Takes a TokenTypes and returns whether it names a built-in function that is called in expressions
+fn is_function_token(token: TokenTypes) -> bool {
+    return token == TokenTypes::REGRESSION
+        || token == TokenTypes::REGRESSIONA
+        || token == TokenTypes::REGRESSIONB
+        || token == TokenTypes::CORRELATION
+        || token == TokenTypes::MEAN
+        || token == TokenTypes::STDDEV
+        || token == TokenTypes::ABS
+        || token == TokenTypes::LOG
+        || token == TokenTypes::SLICE
+        || token == TokenTypes::CONCAT;
+}
+
+// Helper function
+// Takes a TokenTypes and returns whether it is a comparison operator
+fn is_comparison_token(token: TokenTypes) -> bool {
+    return token == TokenTypes::LESS
+        || token == TokenTypes::GREATER
+        || token == TokenTypes::LESSEQUAL
+        || token == TokenTypes::GREATEREQUAL
+        || token == TokenTypes::EQUAL
+        || token == TokenTypes::NOTEQUAL;
+}
+
+// Helper function
+// Takes the Token a syntax error was found at and the keywords that could have been meant there
+// Returns a suggestion line for the error message if the Token is an identifier close to one of them, e.g.
+// 'vectr' or 'proces', which is then probably a misspelling of it, or else nothing
+fn suggestion_line<'a>(token: &Token, keywords: impl IntoIterator<Item = &'a str>) -> String {
+    if token.token != TokenTypes::ID {
+        return String::new();
+    }
+    return match closest_match(&token.lexeme, keywords) {
+        Some(keyword) => format!("; {}: suggestion: {}\n", token.location, keyword),
+        None => String::new(),
+    };
+}
+
+// Helper function
+// Takes a list of the tokens or items that were expected, quoted or described, e.g. "','" or "an identifier"
+// Returns them as a list for a message, e.g. "',', ')' or an operator"
+fn expected_list(expected: &[&str]) -> String {
+    return match expected.split_last() {
+        Some((last, [])) => String::from(*last),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::from("nothing"),
+    };
+}
+
+// Helper function
+// Takes a Token and returns a help line for an error message if it can only be used in one section, e.g. 'read'
+// in the process section, or else nothing
+fn section_help(token: &Token) -> String {
+    let section: &str = match token.token {
+        TokenTypes::READ | TokenTypes::READJSON | TokenTypes::READLINES | TokenTypes::READTABLE => {
+            "input"
+        }
+        TokenTypes::PRINT
+        | TokenTypes::WRITE
+        | TokenTypes::APPEND
+        | TokenTypes::PLOT
+        | TokenTypes::HISTOGRAM => "output",
+        _ => return String::new(),
+    };
+    return format!(
+        "; help: '{}' can only be used in the {} section\n",
+        token.lexeme, section
+    );
+}
+
+// Helper function
+// Takes the Token a syntax error was found at and what was expected there
+// Returns the message to panic with
+fn syntax_error(token: &Token, expected: &[&str]) -> String {
+    return format!(
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Syntax error at '{}', expected {}.\n{}{}\n",
+        token.location,
+        token.lexeme,
+        expected_list(expected),
+        suggestion_line(token, reserved_lexemes().into_keys()),
+        section_help(token)
+    );
+}
+
 // Helper function
 // Takes two integers, i and i_max, and returns i incremented by 1
 // Function panics if i is greater than i_max - 1
 fn increment_i(i: usize, i_max: usize) -> usize {
     assert!(
         i < i_max - 1,
-        "\n\n; SYNTAX ERROR!\n; Program incomplete!\n\n"
+        "\n\n; SYNTAX ERROR[E0002]!\n; Program incomplete!\n\n"
     );
     return i + 1;
 }
 
-// DataDef Parser
-// Parses RHS for the datadef rule of the grammar
-// Takes an unsigned integer, a vector of Tokens, a Flag (which is thrown away), and a String
-// Returns a tuple with an integer and a String
+// Const Parser
+// Parses RHS for the const rule of the grammar, e.g. 'const alpha = 0.05' or 'const file = "sales.csv"'
+// Takes an unsigned integer and a vector of Tokens, starting at the 'const' Token
+// Returns a tuple with an integer and a Const
 // Function panics if syntax errors are found
-fn datadef_parser(
-    start_index: usize,
-    tokens: &Vec<Token>,
-    _: &Flag,
-    prog_output: String,
-) -> (usize, String) {
+fn const_parser(start_index: usize, tokens: &[Token]) -> (usize, Const) {
     let mut i: usize = start_index;
     let num_tokens: usize = tokens.len();
-    let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = get_next_token(i, tokens).location;
 
+    i = increment_i(i, num_tokens);
+    let mut curr_token: Token = get_next_token(i, tokens);
     assert!(
         curr_token.token == TokenTypes::ID,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        "{}",
+        syntax_error(&curr_token, &["an identifier"])
     );
-
+    let id: String = curr_token.lexeme;
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
     assert!(
-        curr_token.token == TokenTypes::COLON,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::ASSIGN,
+        "{}",
+        syntax_error(&curr_token, &["'='"])
     );
-
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
+    // The value is a literal, and a negative number is kept as one lexeme
+    let mut value: String = String::new();
+    if curr_token.token == TokenTypes::MINUS {
+        value.push('-');
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::NUM,
+            "{}",
+            syntax_error(&curr_token, &["a number"])
+        );
+    }
     assert!(
-        curr_token.token == TokenTypes::VECTOR || curr_token.token == TokenTypes::NUMBER,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
+        curr_token.token == TokenTypes::NUM
+            || curr_token.token == TokenTypes::STRING
+            || curr_token.token == TokenTypes::TRUE
+            || curr_token.token == TokenTypes::FALSE,
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a number, a string, 'true' or 'false' as the value of constant '{}' instead of '{}'.\n\n",
+        curr_token.location,
+        id,
         curr_token.lexeme
     );
+    value.push_str(&curr_token.lexeme);
     i = increment_i(i, num_tokens);
-    return (i, prog_output);
+    return (
+        i,
+        Const {
+            id,
+            value,
+            location,
+        },
+    );
 }
 
-// InputOp Parser
-// Parses RHS for the inputop rule of the grammar
-// Takes an unsigned integer, a vector of Tokens, a Flag, and a String
-// Returns a tuple with an integer and a String
+// DataDef Parser
+// Parses RHS for the datadef rule of the grammar, an 'id : type' entry with an optional '= expression'
+// initialiser, or a constant
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and a DataEntry
 // Function panics if syntax errors are found
-fn inputop_parser(
-    start_index: usize,
-    tokens: &Vec<Token>,
-    flag: &Flag,
-    mut prog_output: String,
-) -> (usize, String) {
+fn datadef_parser(start_index: usize, tokens: &[Token]) -> (usize, DataEntry) {
     let mut i: usize = start_index;
     let num_tokens: usize = tokens.len();
     let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = curr_token.location.clone();
+
+    if curr_token.token == TokenTypes::CONST {
+        let constant: Const;
+        (i, constant) = const_parser(i, tokens);
+        return (i, DataEntry::Const(constant));
+    }
 
     assert!(
         curr_token.token == TokenTypes::ID,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        "{}",
+        syntax_error(&curr_token, &["an identifier", "'const'"])
     );
     let id: String = curr_token.lexeme;
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, tokens);
 
-    assert!(
-        curr_token.token == TokenTypes::ASSIGN,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
-    );
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
+    // 'x = vector' is how data entries were once declared
+    let is_type: bool = tokens.get(i + 1).is_some_and(|token| {
+        matches!(
+            token.token,
+            TokenTypes::VECTOR | TokenTypes::NUMBER | TokenTypes::TABLE
+        )
+    });
     assert!(
-        curr_token.token == TokenTypes::READ,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token != TokenTypes::ASSIGN || !is_type,
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Data entries are declared with ':', e.g. '{} : {}'.\n; {}: fix: :\n; help: '--edition 2023' accepts the '=' of older programs, with a warning\n\n",
+        curr_token.location,
+        id,
+        tokens[i + 1].lexeme,
+        curr_token.location
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, tokens);
-
     assert!(
-        curr_token.token == TokenTypes::LPAREN,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::COLON,
+        "{}",
+        syntax_error(&curr_token, &["':'"])
     );
+
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
     assert!(
-        curr_token.token == TokenTypes::STRING,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::VECTOR
+            || curr_token.token == TokenTypes::NUMBER
+            || curr_token.token == TokenTypes::TABLE,
+        "{}",
+        syntax_error(&curr_token, &["'vector'", "'number'", "'table'"])
     );
-    let str: String = curr_token.lexeme;
+    let data_type: DataType = if curr_token.token == TokenTypes::VECTOR {
+        DataType::Vector
+    } else if curr_token.token == TokenTypes::TABLE {
+        DataType::Table
+    } else {
+        DataType::Number
+    };
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
-    assert!(
-        curr_token.token == TokenTypes::COMMA,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+    let mut init: Option<Expr> = None;
+    if curr_token.token == TokenTypes::ASSIGN {
+        i = increment_i(i, num_tokens);
+        let expr: Expr;
+        (i, expr) = expression_parser(i, tokens);
+        init = Some(expr);
+    }
+    return (
+        i,
+        DataEntry::Def(DataDef {
+            id,
+            data_type,
+            init,
+            location,
+        }),
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, tokens);
+}
+
+// Define Parser
+// Parses RHS for the define rule of the grammar, e.g. 'cv(v) = stddev(v) / mean(v)'
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and a Define
+// Function panics if syntax errors are found
+fn define_parser(start_index: usize, tokens: &[Token]) -> (usize, Define) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = curr_token.location.clone();
 
     assert!(
-        curr_token.token == TokenTypes::TRUE || curr_token.token == TokenTypes::FALSE,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::ID,
+        "{}",
+        syntax_error(&curr_token, &["a function name"])
     );
-    let mut bool: String = curr_token.lexeme;
+    let name: String = curr_token.lexeme;
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
     assert!(
-        curr_token.token == TokenTypes::COMMA,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::LPAREN,
+        "{}",
+        syntax_error(&curr_token, &["'('"])
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, tokens);
+
+    let mut params: Vec<String> = Vec::new();
+    loop {
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::ID,
+            "{}",
+            syntax_error(&curr_token, &["a parameter name"])
+        );
+        params.push(curr_token.lexeme);
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        if curr_token.token != TokenTypes::COMMA {
+            break;
+        }
+    }
 
     assert!(
-        curr_token.token == TokenTypes::NUM,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::RPAREN,
+        "{}",
+        syntax_error(&curr_token, &["','", "')'"])
     );
-    let num: String = curr_token.lexeme;
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
     assert!(
-        curr_token.token == TokenTypes::RPAREN,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::ASSIGN,
+        "{}",
+        syntax_error(&curr_token, &["'='"])
     );
     i = increment_i(i, num_tokens);
 
-    if flag == &Flag::Prolog {
-        prog_output
-            .push_str(format!("\n   load_data_column({str}, {bool}, {num}, {id}),").as_str());
-    } else if flag == &Flag::Scheme {
-        bool = String::from(bool.chars().nth(0).unwrap());
-        prog_output.push_str(format!("(define {id} (read-csv {str} #{bool} {num}))\n").as_str());
-    }
-
-    return (i, prog_output);
+    let body: Expr;
+    (i, body) = expression_parser(i, tokens);
+    return (
+        i,
+        Define {
+            name,
+            params,
+            body,
+            location,
+        },
+    );
 }
 
-// ProcessOp Parser
-// Parses RHS for the processop rule of the grammar
-// Takes an unsigned integer, a vector of Tokens, a Flag, and a String
-// Returns a tuple with an integer and a String
+// InputOp Parser
+// Parses RHS for the inputop rule of the grammar
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and an InputOp
 // Function panics if syntax errors are found
-fn processop_parser(
-    start_index: usize,
-    tokens: &Vec<Token>,
-    flag: &Flag,
-    mut prog_output: String,
-) -> (usize, String) {
+fn inputop_parser(start_index: usize, tokens: &[Token]) -> (usize, InputOp) {
     let mut i: usize = start_index;
     let num_tokens: usize = tokens.len();
     let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = curr_token.location.clone();
 
     assert!(
         curr_token.token == TokenTypes::ID,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        "{}",
+        syntax_error(&curr_token, &["an identifier"])
     );
     let id: String = curr_token.lexeme;
     i = increment_i(i, num_tokens);
@@ -373,303 +789,1416 @@ fn processop_parser(
 
     assert!(
         curr_token.token == TokenTypes::ASSIGN,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        "{}",
+        syntax_error(&curr_token, &["'='"])
     );
     i = increment_i(i, num_tokens);
     curr_token = get_next_token(i, tokens);
 
-    let func: String = curr_token.lexeme;
-    if curr_token.token == TokenTypes::REGRESSIONA
-        || curr_token.token == TokenTypes::REGRESSIONB
-        || curr_token.token == TokenTypes::CORRELATION
-    {
-        i = increment_i(i, num_tokens);
-        curr_token = get_next_token(i, tokens);
+    assert!(
+        curr_token.token == TokenTypes::READ
+            || curr_token.token == TokenTypes::READJSON
+            || curr_token.token == TokenTypes::READLINES
+            || curr_token.token == TokenTypes::READTABLE,
+        "{}",
+        syntax_error(
+            &curr_token,
+            &["'read'", "'read_json'", "'read_lines'", "'read_table'"]
+        )
+    );
+    let func: Token = curr_token;
+    i = increment_i(i, num_tokens);
+    curr_token = get_next_token(i, tokens);
+
+    assert!(
+        curr_token.token == TokenTypes::LPAREN,
+        "{}",
+        syntax_error(&curr_token, &["'('"])
+    );
+    i = increment_i(i, num_tokens);
+    curr_token = get_next_token(i, tokens);
+
+    assert!(
+        curr_token.token == TokenTypes::STRING,
+        "{}",
+        syntax_error(&curr_token, &["a file name"])
+    );
+    let file: String = curr_token.lexeme;
+    i = increment_i(i, num_tokens);
+    curr_token = get_next_token(i, tokens);
+
+    let source: Source;
+    // The arguments that may still follow when the closing ')' is not found
+    let mut closing: &[&str] = &["')'"];
+    if func.token == TokenTypes::READ {
         assert!(
-            curr_token.token == TokenTypes::LPAREN,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            curr_token.token == TokenTypes::COMMA,
+            "{}",
+            syntax_error(&curr_token, &["','"])
         );
-
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
+
         assert!(
-            curr_token.token == TokenTypes::ID,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            curr_token.token == TokenTypes::TRUE || curr_token.token == TokenTypes::FALSE,
+            "{}",
+            syntax_error(&curr_token, &["'true'", "'false'"])
         );
-        let param1: String = curr_token.lexeme;
-
+        let header: bool = curr_token.token == TokenTypes::TRUE;
+        let header_location: Location = curr_token.location.clone();
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
+
         assert!(
             curr_token.token == TokenTypes::COMMA,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            "{}",
+            syntax_error(&curr_token, &["','"])
         );
-
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
+
+        // The column is either a numeric index or, when the file has a header row, a column name
         assert!(
-            curr_token.token == TokenTypes::ID,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            (curr_token.token == TokenTypes::NUM && !curr_token.lexeme.contains('.'))
+                || curr_token.token == TokenTypes::STRING,
+            "{}",
+            syntax_error(&curr_token, &["a column index", "a column name"])
         );
-        let param2: String = curr_token.lexeme;
-
-        i = increment_i(i, num_tokens);
-        curr_token = get_next_token(i, tokens);
         assert!(
-            curr_token.token == TokenTypes::RPAREN,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            curr_token.token == TokenTypes::NUM || header,
+            "\n\n; SYNTAX ERROR[E0006]!\n; {}: Column name {} requires a header row, expected 'true' instead of 'false'.\n; {}: fix: true\n\n",
+            curr_token.location,
+            curr_token.lexeme,
+            header_location
         );
-
-        if flag == &Flag::Prolog {
-            prog_output.push_str(format!("\n   {func}({param1}, {param2}, {id}),").as_str());
-        } else if flag == &Flag::Scheme {
-            prog_output.push_str(format!("(define {id} ({func} {param1} {param2}))\n").as_str());
-        }
-    } else if curr_token.token == TokenTypes::MEAN || curr_token.token == TokenTypes::STDDEV {
+        // A constant may give a negative index
+        let column: Column = if curr_token.token == TokenTypes::STRING {
+            Column::Name(String::from(
+                &curr_token.lexeme[1..curr_token.lexeme.len() - 1],
+            ))
+        } else {
+            assert!(
+                !curr_token.lexeme.starts_with('-'),
+                "\n\n; SYNTAX ERROR[E0002]!\n; {}: Column index {} is negative, columns are counted from 0.\n\n",
+                curr_token.location,
+                curr_token.lexeme
+            );
+            Column::Index(whole_number_parser(&curr_token, "a column index"))
+        };
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
+
+        // Optional named arguments follow the positional ones, e.g. delimiter="\t"
+        let mut delimiter: Option<String> = None;
+        while curr_token.token == TokenTypes::COMMA {
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            if curr_token.token == TokenTypes::ID && curr_token.lexeme != "delimiter" {
+                panic!(
+                    "\n\n; SYNTAX ERROR[E0005]!\n; {}: Unknown named argument '{}' to 'read', expected 'delimiter'.\n; {}: suggestion: delimiter\n\n",
+                    curr_token.location, curr_token.lexeme, curr_token.location
+                );
+            }
+            assert!(
+                curr_token.token == TokenTypes::ID,
+                "{}",
+                syntax_error(&curr_token, &["'delimiter'"])
+            );
+            assert!(
+                delimiter.is_none(),
+                "\n\n; SYNTAX ERROR[E0005]!\n; {}: Named argument 'delimiter' given more than once.\n\n",
+                curr_token.location
+            );
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+
+            assert!(
+                curr_token.token == TokenTypes::ASSIGN,
+                "{}",
+                syntax_error(&curr_token, &["'='"])
+            );
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+
+            assert!(
+                curr_token.token == TokenTypes::STRING && is_single_char_string(&curr_token.lexeme),
+                "\n\n; SYNTAX ERROR[E0005]!\n; {}: Expected a single character delimiter instead of '{}'.\n\n",
+                curr_token.location,
+                curr_token.lexeme
+            );
+            delimiter = Some(curr_token.lexeme);
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+        }
+
+        if delimiter.is_none() {
+            closing = &["','", "')'"];
+        }
+        source = Source::Csv {
+            file,
+            header,
+            column,
+            delimiter,
+        };
+    } else if func.token == TokenTypes::READJSON {
+        // The field is optional; without it the file must hold a plain array of numbers
+        let mut field: Option<String> = None;
+        if curr_token.token == TokenTypes::COMMA {
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::STRING,
+                "{}",
+                syntax_error(&curr_token, &["a field name"])
+            );
+            field = Some(curr_token.lexeme);
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+        } else {
+            closing = &["','", "')'"];
+        }
+        source = Source::Json { file, field };
+    } else if func.token == TokenTypes::READTABLE {
+        // read_table(file, header) loads every column of the file, named by the header row if there is one
         assert!(
-            curr_token.token == TokenTypes::LPAREN,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            curr_token.token == TokenTypes::COMMA,
+            "{}",
+            syntax_error(&curr_token, &["','"])
         );
-
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
         assert!(
-            curr_token.token == TokenTypes::ID,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
+            curr_token.token == TokenTypes::TRUE || curr_token.token == TokenTypes::FALSE,
+            "{}",
+            syntax_error(&curr_token, &["'true'", "'false'"])
         );
-        let param1: String = curr_token.lexeme;
-
+        let header: bool = curr_token.token == TokenTypes::TRUE;
         i = increment_i(i, num_tokens);
         curr_token = get_next_token(i, tokens);
-        assert!(
-            curr_token.token == TokenTypes::RPAREN,
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            curr_token.lexeme
-        );
-
-        if flag == &Flag::Prolog {
-            prog_output.push_str(format!("\n   {func}({param1}, {id}),").as_str());
-        } else if flag == &Flag::Scheme {
-            prog_output.push_str(format!("(define {id} ({func} {param1}))\n").as_str());
-        }
+        source = Source::Table { file, header };
     } else {
-        panic!(
-            "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-            func // This is 'curr_token.lexeme,' renamed
-        );
+        source = Source::Lines { file };
     }
 
-    return (i + 1, prog_output);
-}
-
-// OutputOp Parser
-// Parses RHS for the outputop rule of the grammar
-// Takes an unsigned integer, a vector of Tokens, a Flag, and a String
-// Returns a tuple with an integer and a String
+    assert!(
+        curr_token.token == TokenTypes::RPAREN,
+        "{}",
+        syntax_error(&curr_token, closing)
+    );
+    i = increment_i(i, num_tokens);
+
+    return (
+        i,
+        InputOp {
+            id,
+            source,
+            location,
+        },
+    );
+}
+
+// ProcessOp Parser
+// Parses RHS for the processop rule of the grammar
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and a ProcessOp
 // Function panics if syntax errors are found
-fn outputop_parser(
-    start_index: usize,
-    tokens: &Vec<Token>,
-    flag: &Flag,
-    mut prog_output: String,
-) -> (usize, String) {
-    let i: usize = start_index;
-    let curr_token: Token = get_next_token(i, tokens);
+fn processop_parser(start_index: usize, tokens: &[Token]) -> (usize, ProcessOp) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = curr_token.location.clone();
 
     assert!(
-        curr_token.token == TokenTypes::STRING || curr_token.token == TokenTypes::ID,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::ID,
+        "{}",
+        syntax_error(&curr_token, &["an identifier"])
     );
-    let str_or_id = curr_token.lexeme;
-    if flag == &Flag::Prolog {
-        prog_output.push_str(format!("\n   writeIn({str_or_id}),").as_str());
-    } else if flag == &Flag::Scheme {
-        prog_output.push_str(format!("(display {str_or_id})\n(newline)\n").as_str());
+    let mut ids: Vec<String> = vec![curr_token.lexeme];
+    i = increment_i(i, num_tokens);
+    curr_token = get_next_token(i, tokens);
+
+    // Several identifiers receive the results of one call, e.g. 'a, b, rsq = regression(x, y)'
+    while curr_token.token == TokenTypes::COMMA {
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::ID,
+            "{}",
+            syntax_error(&curr_token, &["an identifier"])
+        );
+        ids.push(curr_token.lexeme);
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
     }
-    return (i + 1, prog_output);
+
+    assert!(
+        curr_token.token == TokenTypes::ASSIGN,
+        "{}",
+        syntax_error(&curr_token, &["','", "'='"])
+    );
+    i = increment_i(i, num_tokens);
+
+    let expr: Expr;
+    (i, expr) = expression_parser(i, tokens);
+    return (
+        i,
+        ProcessOp {
+            ids,
+            expr,
+            location,
+        },
+    );
 }
 
-// Special helper function
-// Parses comma-spearated datadef,inputop, processop, or outputop nonterminals of the grammar
-// Takes an unsigned integer, a vector of Tokens, a function (datadef, inputop, processop, or outputop), a Flag, and a String
-// Returns a tuple with an integer and a String
-fn special_parser(
-    start_index: usize,
-    tokens: &Vec<Token>,
-    function: fn(usize, &Vec<Token>, &Flag, String) -> (usize, String),
-    flag: &Flag,
-    mut prog_output: String,
-) -> (usize, String) {
+// Expression Parser
+// Parses RHS for the expression rule of the grammar: term { ('+' | '-') term }
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and an Expr
+// Function panics if syntax errors are found
+fn expression_parser(start_index: usize, tokens: &[Token]) -> (usize, Expr) {
     let mut i: usize = start_index;
     let num_tokens: usize = tokens.len();
-    let mut curr_token: Token;
+    let mut expr: Expr;
 
-    (i, prog_output) = function(i, tokens, flag, prog_output);
+    (i, expr) = term_parser(i, tokens);
+    let mut curr_token: Token = get_next_token(i, tokens);
+    while curr_token.token == TokenTypes::PLUS || curr_token.token == TokenTypes::MINUS {
+        let op: char = curr_token.lexeme.chars().next().unwrap();
+        let rhs: Expr;
+        i = increment_i(i, num_tokens);
+        (i, rhs) = term_parser(i, tokens);
+        expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+        curr_token = get_next_token(i, tokens);
+    }
+    return (i, expr);
+}
 
-    curr_token = get_next_token(i, tokens);
+// Term Parser
+// Parses RHS for the term rule of the grammar: factor { ('*' | '/') factor }
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and an Expr
+// Function panics if syntax errors are found
+fn term_parser(start_index: usize, tokens: &[Token]) -> (usize, Expr) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut expr: Expr;
 
-    while curr_token.token == TokenTypes::COMMA {
+    (i, expr) = factor_parser(i, tokens);
+    let mut curr_token: Token = get_next_token(i, tokens);
+    while curr_token.token == TokenTypes::TIMES || curr_token.token == TokenTypes::DIVIDE {
+        let op: char = curr_token.lexeme.chars().next().unwrap();
+        let rhs: Expr;
         i = increment_i(i, num_tokens);
-        (i, prog_output) = function(i, tokens, flag, prog_output);
+        (i, rhs) = factor_parser(i, tokens);
+        expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
         curr_token = get_next_token(i, tokens);
     }
-    return (i, prog_output);
+    return (i, expr);
 }
 
-// Program Parser
-// Parses RHS for the program rule of the grammar
-// Takes a vector of Tokens, a Flag, and a String
-// Returns a String corresponding to the requested Prolog/Scheme output
+// Factor Parser
+// Parses RHS for the factor rule of the grammar: NUM | ID | call | filter | '(' expression ')' | '-' factor
+// A call is a statistics function or a user function followed by a parenthesised argument list
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and an Expr
 // Function panics if syntax errors are found
-fn program_parser(tokens: Vec<Token>, flag: Flag, mut prog_output: String) -> String {
-    let mut i: usize = 0;
+fn factor_parser(start_index: usize, tokens: &[Token]) -> (usize, Expr) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token = get_next_token(i, tokens);
+    let expr: Expr;
+
+    if curr_token.token == TokenTypes::NUM {
+        expr = Expr::Num(curr_token.lexeme);
+        i = increment_i(i, num_tokens);
+    } else if curr_token.token == TokenTypes::MINUS {
+        let operand: Expr;
+        i = increment_i(i, num_tokens);
+        (i, operand) = factor_parser(i, tokens);
+        expr = Expr::Negate(Box::new(operand));
+    } else if curr_token.token == TokenTypes::LPAREN {
+        let inner: Expr;
+        i = increment_i(i, num_tokens);
+        (i, inner) = expression_parser(i, tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::RPAREN,
+            "{}",
+            syntax_error(&curr_token, &["an operator", "')'"])
+        );
+        i = increment_i(i, num_tokens);
+        expr = inner;
+    } else if curr_token.token == TokenTypes::FILTER {
+        // filter(vector, comparison threshold), e.g. 'filter(x, > 0)'
+        let vector: Expr;
+        let threshold: Expr;
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::LPAREN,
+            "{}",
+            syntax_error(&curr_token, &["'('"])
+        );
+        i = increment_i(i, num_tokens);
+        (i, vector) = expression_parser(i, tokens);
+
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::COMMA,
+            "{}",
+            syntax_error(&curr_token, &["an operator", "','"])
+        );
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+
+        assert!(
+            is_comparison_token(curr_token.token),
+            "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a comparison ('<', '>', '<=', '>=', '==' or '!=') instead of '{}'.\n\n",
+            curr_token.location,
+            curr_token.lexeme
+        );
+        let comparison: String = curr_token.lexeme;
+        i = increment_i(i, num_tokens);
+        (i, threshold) = expression_parser(i, tokens);
+
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::RPAREN,
+            "{}",
+            syntax_error(&curr_token, &["an operator", "')'"])
+        );
+        i = increment_i(i, num_tokens);
+        expr = Expr::Filter(Box::new(vector), comparison, Box::new(threshold));
+    } else if curr_token.token == TokenTypes::ID || is_function_token(curr_token.token) {
+        let is_id: bool = curr_token.token == TokenTypes::ID;
+        let name: String = curr_token.lexeme;
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+
+        if is_id && curr_token.token == TokenTypes::PERIOD {
+            // Column access by name, e.g. 't.revenue'; a column may share its name with a keyword such as 'mean'
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.lexeme.starts_with(|c: char| c.is_ascii_lowercase()),
+                "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a column name after '{}.' instead of '{}'.\n\n",
+                curr_token.location,
+                name,
+                curr_token.lexeme
+            );
+            i = increment_i(i, num_tokens);
+            expr = Expr::Column(Box::new(Expr::Id(name)), Column::Name(curr_token.lexeme));
+        } else if is_id && curr_token.token == TokenTypes::LBRACKET {
+            // Column access by index, e.g. 't[2]'
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::NUM && !curr_token.lexeme.contains('.'),
+                "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a column index after '{}[' instead of '{}'.\n\n",
+                curr_token.location,
+                name,
+                curr_token.lexeme
+            );
+            let index: usize = whole_number_parser(&curr_token, "a column index");
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::RBRACKET,
+                "{}",
+                syntax_error(&curr_token, &["']'"])
+            );
+            i = increment_i(i, num_tokens);
+            expr = Expr::Column(Box::new(Expr::Id(name)), Column::Index(index));
+        } else if is_id && curr_token.token != TokenTypes::LPAREN {
+            expr = Expr::Id(name);
+        } else {
+            assert!(
+                curr_token.token == TokenTypes::LPAREN,
+                "{}",
+                syntax_error(&curr_token, &["'('"])
+            );
+            let mut args: Vec<Expr> = Vec::new();
+            loop {
+                let arg: Expr;
+                i = increment_i(i, num_tokens);
+                (i, arg) = expression_parser(i, tokens);
+                args.push(arg);
+                curr_token = get_next_token(i, tokens);
+                if curr_token.token != TokenTypes::COMMA {
+                    break;
+                }
+            }
+            assert!(
+                curr_token.token == TokenTypes::RPAREN,
+                "{}",
+                syntax_error(&curr_token, &["an operator", "','", "')'"])
+            );
+            i = increment_i(i, num_tokens);
+            expr = Expr::Call(name, args);
+        }
+    } else {
+        panic!("{}", syntax_error(&curr_token, &["an expression"]));
+    }
+
+    return (i, expr);
+}
+
+// OutputOp Parser
+// Parses RHS for the outputop rule of the grammar
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and an OutputOp paired with the Location it starts at
+// Function panics if syntax errors are found
+fn outputop_parser(start_index: usize, tokens: &[Token]) -> (usize, (OutputOp, Location)) {
+    let mut i: usize = start_index;
     let num_tokens: usize = tokens.len();
-    let mut curr_token: Token = get_next_token(i, &tokens);
-    if flag == Flag::Prolog {
-        prog_output.push_str("main :-");
+    let mut curr_token: Token = get_next_token(i, tokens);
+    let location: Location = curr_token.location.clone();
+
+    if curr_token.token == TokenTypes::PRINT
+        || curr_token.token == TokenTypes::WRITE
+        || curr_token.token == TokenTypes::APPEND
+    {
+        // print(item, ...) puts every item on one line, e.g. 'print("a = ", a:3)'
+        // write(file, item, ...) and append(file, item, ...) put them on one comma-separated line of a file
+        let func: TokenTypes = curr_token.token;
+        let mut file: String = String::new();
+        let items: Vec<PrintItem>;
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::LPAREN,
+            "{}",
+            syntax_error(&curr_token, &["'('"])
+        );
+        i = increment_i(i, num_tokens);
+
+        if func != TokenTypes::PRINT {
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::STRING,
+                "{}",
+                syntax_error(&curr_token, &["a file name"])
+            );
+            file = curr_token.lexeme;
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::COMMA,
+                "{}",
+                syntax_error(&curr_token, &["','"])
+            );
+            i = increment_i(i, num_tokens);
+        }
+
+        (i, items) = special_parser(i, tokens, printitem_parser);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::RPAREN,
+            "{}",
+            syntax_error(&curr_token, &["','", "')'"])
+        );
+
+        let outputop: OutputOp = if func == TokenTypes::PRINT {
+            OutputOp::Print(items)
+        } else {
+            OutputOp::Write {
+                file,
+                items,
+                append: func == TokenTypes::APPEND,
+            }
+        };
+        return (i + 1, (outputop, location));
     }
+
+    if curr_token.token == TokenTypes::PLOT || curr_token.token == TokenTypes::HISTOGRAM {
+        // plot(x, y, file) draws a scatter plot with the fitted regression line,
+        // histogram(x, file) draws the distribution of a vector
+        let func: TokenTypes = curr_token.token;
+        let num_vectors: usize = if func == TokenTypes::PLOT { 2 } else { 1 };
+        let mut vectors: Vec<String> = Vec::new();
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::LPAREN,
+            "{}",
+            syntax_error(&curr_token, &["'('"])
+        );
+
+        while vectors.len() < num_vectors {
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::ID,
+                "{}",
+                syntax_error(&curr_token, &["an identifier"])
+            );
+            vectors.push(curr_token.lexeme);
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::COMMA,
+                "{}",
+                syntax_error(&curr_token, &["','"])
+            );
+        }
+
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::STRING,
+            "{}",
+            syntax_error(&curr_token, &["a file name"])
+        );
+        let file: String = curr_token.lexeme;
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::RPAREN,
+            "{}",
+            syntax_error(&curr_token, &["')'"])
+        );
+
+        let outputop: OutputOp = if func == TokenTypes::PLOT {
+            OutputOp::Plot {
+                x: vectors.remove(0),
+                y: vectors.remove(0),
+                file,
+            }
+        } else {
+            OutputOp::Histogram {
+                values: vectors.remove(0),
+                file,
+            }
+        };
+        return (i + 1, (outputop, location));
+    }
+
     assert!(
-        curr_token.token == TokenTypes::DATA,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
-    );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, &tokens);
-    assert!(
-        curr_token.token == TokenTypes::COLON,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::STRING || curr_token.token == TokenTypes::ID,
+        "{}",
+        syntax_error(
+            &curr_token,
+            &[
+                "a string",
+                "an identifier",
+                "'print'",
+                "'write'",
+                "'append'",
+                "'plot'",
+                "'histogram'"
+            ]
+        )
     );
+    let outputop: OutputOp = if curr_token.token == TokenTypes::STRING {
+        OutputOp::Str(curr_token.lexeme)
+    } else {
+        OutputOp::Id(curr_token.lexeme)
+    };
+    return (i + 1, (outputop, location));
+}
 
-    i = increment_i(i, num_tokens);
-    (i, prog_output) = special_parser(i, &tokens, datadef_parser, &flag, prog_output);
+// Helper function
+// Takes a NUM Token that holds a whole number, e.g. a column index or a number of decimals, and what it stands for
+// Returns the number
+// Function panics if the number is too large to be one
+fn whole_number_parser(token: &Token, what: &str) -> usize {
+    match token.lexeme.parse() {
+        Ok(number) => return number,
+        Err(_) => panic!(
+            "\n\n; SYNTAX ERROR[E0002]!\n; {}: '{}' is too large for {}.\n\n",
+            token.location, token.lexeme, what
+        ),
+    }
+}
+
+// PrintItem Parser
+// Parses RHS for the printitem rule of the grammar: STRING | ID [':' NUM]
+// The optional NUM is the number of decimals the value is printed with
+// Takes an unsigned integer and a vector of Tokens
+// Returns a tuple with an integer and a PrintItem
+// Function panics if syntax errors are found
+fn printitem_parser(start_index: usize, tokens: &[Token]) -> (usize, PrintItem) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token = get_next_token(i, tokens);
 
-    curr_token = get_next_token(i, &tokens);
     assert!(
-        curr_token.token == TokenTypes::INPUT,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::STRING || curr_token.token == TokenTypes::ID,
+        "{}",
+        syntax_error(&curr_token, &["a string", "an identifier"])
     );
+    if curr_token.token == TokenTypes::STRING {
+        i = increment_i(i, num_tokens);
+        return (i, PrintItem::Str(curr_token.lexeme));
+    }
+
+    let id: String = curr_token.lexeme;
+    let mut precision: Option<usize> = None;
     i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, &tokens);
-    assert!(
-        curr_token.token == TokenTypes::COLON,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+    curr_token = get_next_token(i, tokens);
+    if curr_token.token == TokenTypes::COLON {
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::NUM && !curr_token.lexeme.contains('.'),
+            "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a number of decimals after '{}:' instead of '{}'.\n\n",
+            curr_token.location,
+            id,
+            curr_token.lexeme
+        );
+        precision = Some(whole_number_parser(&curr_token, "a number of decimals"));
+        i = increment_i(i, num_tokens);
+    }
+    return (i, PrintItem::Id(id, precision));
+}
+
+// Special helper function
+// Parses comma-spearated datadef, define, inputop, processop, outputop, or printitem nonterminals of the grammar
+// Takes an unsigned integer, a vector of Tokens, and a function (datadef, define, inputop, processop, outputop, or printitem)
+// Returns a tuple with an integer and a vector of the parsed nonterminals
+fn special_parser<T>(
+    start_index: usize,
+    tokens: &[Token],
+    function: fn(usize, &[Token]) -> (usize, T),
+) -> (usize, Vec<T>) {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token;
+    let mut items: Vec<T> = Vec::new();
+    let mut item: T;
+
+    (i, item) = function(i, tokens);
+    items.push(item);
+
+    curr_token = get_next_token(i, tokens);
+
+    while curr_token.token == TokenTypes::COMMA {
+        let comma: Location = curr_token.location;
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        // A ',' after the last item is a common mistake, e.g. before 'process:' or the ')' of 'print'
+        assert!(
+            !is_section_token(curr_token.token) && curr_token.token != TokenTypes::RPAREN,
+            "\n\n; SYNTAX ERROR[E0002]!\n; {}: Syntax error at '{}', expected another item after ','.\n; {}: fix: \n; help: only the items before the last one end with ','\n\n",
+            curr_token.location,
+            curr_token.lexeme,
+            comma
+        );
+        (i, item) = function(i, tokens);
+        items.push(item);
+        curr_token = get_next_token(i, tokens);
+    }
+    return (i, items);
+}
+
+// Helper function
+// Takes a TokenTypes and returns whether it starts a section or an include directive, or ends the program or an
+// included file
+fn is_section_token(token: TokenTypes) -> bool {
+    return matches!(
+        token,
+        TokenTypes::DATA
+            | TokenTypes::DEFINE
+            | TokenTypes::INPUT
+            | TokenTypes::PROCESS
+            | TokenTypes::OUTPUT
+            | TokenTypes::INCLUDE
+            | TokenTypes::END
+            | TokenTypes::EOF
     );
-    i = increment_i(i, num_tokens);
-    (i, prog_output) = special_parser(i, &tokens, inputop_parser, &flag, prog_output);
+}
+
+// Helper function
+// Parses the items of a section, which is empty when the next token starts another section or ends the program
+// Takes an unsigned integer, a vector of Tokens, and a function (datadef, define, inputop, processop, or outputop)
+// Returns a tuple with an integer and a vector of the parsed nonterminals
+fn section_parser<T>(
+    start_index: usize,
+    tokens: &[Token],
+    function: fn(usize, &[Token]) -> (usize, T),
+) -> (usize, Vec<T>) {
+    if is_section_token(get_next_token(start_index, tokens).token) {
+        return (start_index, Vec::new());
+    }
+    return special_parser(start_index, tokens, function);
+}
+
+// Helper function
+// Replaces every use of a constant in an input section by the constant's value, so that constants can be
+// given as arguments to 'read', e.g. 'read(file, true, column)'
+// Identifiers followed by '=' are the ids of input ops or named arguments and are kept
+// Takes a vector of Tokens and the constants declared so far
+// Returns a copy of the Tokens, with the same length, where constants are replaced by their values
+fn constant_resolver(tokens: &[Token], consts: &[Const]) -> Vec<Token> {
+    let mut resolved: Vec<Token> = tokens.to_vec();
+    for (i, token) in resolved.iter_mut().enumerate() {
+        if token.token != TokenTypes::ID
+            || tokens.get(i + 1).map(|next| next.token) == Some(TokenTypes::ASSIGN)
+        {
+            continue;
+        }
+        if let Some(constant) = consts.iter().find(|constant| constant.id == token.lexeme) {
+            token.token = if constant.value.starts_with('"') {
+                TokenTypes::STRING
+            } else if constant.value == "true" {
+                TokenTypes::TRUE
+            } else if constant.value == "false" {
+                TokenTypes::FALSE
+            } else {
+                TokenTypes::NUM
+            };
+            token.lexeme = constant.value.clone();
+        }
+    }
+    return resolved;
+}
+
+// Helper function
+// Replaces the '=' of every data entry declared the 2023 way, e.g. 'x = vector', by ':', and warns about it
+// Takes the index the data section starts at, a vector of Tokens, and the warnings found so far
+// Returns a copy of the Tokens, with the same length, where the data section is written the current way
+fn legacy_resolver(
+    start_index: usize,
+    tokens: &[Token],
+    warnings: &mut Vec<linter::Warning>,
+) -> Vec<Token> {
+    let mut resolved: Vec<Token> = tokens.to_vec();
+    let mut i: usize = start_index;
+    while i + 2 < resolved.len() && !is_section_token(resolved[i].token) {
+        let is_type: bool = matches!(
+            resolved[i + 2].token,
+            TokenTypes::VECTOR | TokenTypes::NUMBER | TokenTypes::TABLE
+        );
+        if resolved[i].token == TokenTypes::ID
+            && resolved[i + 1].token == TokenTypes::ASSIGN
+            && is_type
+        {
+            warnings.push(linter::Warning {
+                code: "W006",
+                location: resolved[i + 1].location.clone(),
+                message: format!(
+                    "'{} = {}' declares a data entry the 2023 way, write '{} : {}'.",
+                    resolved[i].lexeme,
+                    resolved[i + 2].lexeme,
+                    resolved[i].lexeme,
+                    resolved[i + 2].lexeme
+                ),
+                fix: Some(String::from(":")),
+            });
+            resolved[i + 1].token = TokenTypes::COLON;
+            resolved[i + 1].lexeme = String::from(":");
+        }
+        i += 1;
+    }
+    return resolved;
+}
+
+// Helper function
+// Takes the name of an included file, the file that includes it, and the search path
+// Returns the path of the included file, next to the including file or else in the first search path directory
+// holding it, if there is one
+fn include_resolver(name: &str, including: &Path, search_path: &[PathBuf]) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = vec![including.parent().unwrap_or(Path::new("")).join(name)];
+    for directory in search_path {
+        candidates.push(directory.join(name));
+    }
+    return candidates.into_iter().find(|candidate| candidate.is_file());
+}
 
-    curr_token = get_next_token(i, &tokens);
+// Include Parser
+// Splices the sections of the file named by an include directive into the program, where the directive appears
+// The file is looked for next to the file that includes it, then in each search path directory in order
+// A file that was already included is skipped, and a file that (indirectly) includes itself is an error
+// Takes the STRING Token of the directive, the Includes, and the Program parsed so far
+// Function panics if the file cannot be found or read, if it includes itself, or if syntax errors are found
+fn include_parser(token: &Token, includes: &mut Includes, program: &mut Program) {
+    let name: String = interpreter::unescape(&token.lexeme);
+    let including: &Path = includes.stack.last().unwrap();
+    let path: PathBuf = match include_resolver(&name, including, &includes.search_path) {
+        Some(path) => path,
+        None => panic!(
+            "\n\n; FILE ERROR[E0017]!\n; {}: Could not find the included file '{}'.\n\n",
+            token.location, name
+        ),
+    };
+
+    let canonical: PathBuf = fs::canonicalize(&path).unwrap_or(path.clone());
+    let is_same_file =
+        |other: &PathBuf| fs::canonicalize(other).unwrap_or(other.clone()) == canonical;
+    if let Some(start) = includes.stack.iter().position(is_same_file) {
+        let mut cycle: Vec<String> = includes.stack[start..]
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect();
+        cycle.push(path.display().to_string());
+        panic!(
+            "\n\n; FILE ERROR[E0018]!\n; {}: Including '{}' creates a cycle: {}.\n\n",
+            token.location,
+            name,
+            cycle.join(" -> ")
+        );
+    }
+    if includes.included.iter().any(is_same_file) {
+        return;
+    }
+
+    let contents: String = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => panic!(
+            "\n\n; FILE ERROR[E0019]!\n; {}: The contents of the included file '{}' could not be read!\n\n",
+            token.location, name
+        ),
+    };
+    let file: String = path.display().to_string();
+    let mut tokens: Vec<Token> = comment_stripper(lexer(contents, &file));
+    eof_appender(&mut tokens, &file);
+
+    includes.stack.push(path.clone());
+    let i: usize = sections_parser(0, &tokens, includes, program);
+    let curr_token: Token = get_next_token(i, &tokens);
     assert!(
-        curr_token.token == TokenTypes::PROCESS,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        curr_token.token == TokenTypes::EOF,
+        "\n\n; SYNTAX ERROR[E0007]!\n; {}: Included files hold sections only, 'end.' belongs in the main program.\n\n",
+        curr_token.location
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, &tokens);
-    assert!(
-        curr_token.token == TokenTypes::COLON,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+    includes.stack.pop();
+    includes.included.push(path);
+}
+
+// Helper function
+// Takes the index of a Token that neither starts a section nor ends the program and a vector of Tokens
+// Returns the message to panic with, which tells a misspelled section, e.g. 'proces:', a missing ',' after the
+// last item, and a statement in the wrong section apart
+fn section_error(i: usize, tokens: &[Token]) -> String {
+    let curr_token: &Token = &tokens[i];
+    let previous: Option<&Token> = i.checked_sub(1).map(|j| &tokens[j]);
+    let next: Option<TokenTypes> = tokens.get(i + 1).map(|token| token.token);
+    let sections: [&str; 7] = [
+        "data", "define", "input", "process", "output", "include", "end",
+    ];
+    // After the first item of a section, the section may go on with ','
+    let after_item: bool = previous.is_some_and(|token| token.token != TokenTypes::COLON);
+    let expected: &str = if after_item {
+        "',', a section ('data:', 'define:', 'input:', 'process:' or 'output:') or 'end.'"
+    } else {
+        "a section ('data:', 'define:', 'input:', 'process:' or 'output:') or 'end.'"
+    };
+    let mut message: String = format!(
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Syntax error at '{}', expected {}.\n",
+        curr_token.location, curr_token.lexeme, expected
     );
-    i = increment_i(i, num_tokens);
-    (i, prog_output) = special_parser(i, &tokens, processop_parser, &flag, prog_output);
 
-    curr_token = get_next_token(i, &tokens);
-    assert!(
-        curr_token.token == TokenTypes::OUTPUT,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+    let misspelled: String = suggestion_line(curr_token, sections);
+    let starts_item: bool = matches!(
+        curr_token.token,
+        TokenTypes::ID
+            | TokenTypes::STRING
+            | TokenTypes::CONST
+            | TokenTypes::PRINT
+            | TokenTypes::WRITE
+            | TokenTypes::APPEND
+            | TokenTypes::PLOT
+            | TokenTypes::HISTOGRAM
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, &tokens);
+    if !misspelled.is_empty() && (next == Some(TokenTypes::COLON) || !after_item) {
+        message.push_str(&misspelled);
+    } else if let (true, true, Some(previous)) = (after_item, starts_item, previous) {
+        message.push_str(&format!(
+            "; {}: fix: {},\n; help: the items of a section are separated by ','\n",
+            previous.location, previous.lexeme
+        ));
+    } else if curr_token.token == TokenTypes::ASSIGN {
+        message.push_str(
+            "; help: values are given in the input and process sections, and the data section declares entries with ':'\n",
+        );
+    } else {
+        message.push_str(&section_help(curr_token));
+    }
+    message.push('\n');
+    return message;
+}
+
+// Helper function
+// Takes the index an input section starts at and its Tokens, with the constants declared before it resolved
+// Function panics if a 'read' argument is a constant that a data section after the input section declares, since
+// such sections are out of order
+fn late_constant_checker(start_index: usize, tokens: &[Token]) {
+    let mut i: usize = start_index;
+    while i < tokens.len() && !is_section_token(tokens[i].token) {
+        let curr_token: &Token = &tokens[i];
+        // Identifiers followed by '=' are the ids of input ops or named arguments
+        let argument: bool = curr_token.token == TokenTypes::ID
+            && tokens.get(i + 1).map(|next| next.token) != Some(TokenTypes::ASSIGN);
+        let declaration: Option<&Token> = tokens[i..]
+            .windows(2)
+            .find(|pair| pair[0].token == TokenTypes::CONST && pair[1].lexeme == curr_token.lexeme)
+            .map(|pair| &pair[1]);
+        if let (true, Some(declaration)) = (argument, declaration) {
+            panic!(
+                "\n\n; SYNTAX ERROR[E0025]!\n; {}: Constant '{}' is used before the data section that declares it.\n; {}: note: '{}' is declared here\n; help: move the data section before the input section\n\n",
+                curr_token.location, curr_token.lexeme, declaration.location, curr_token.lexeme
+            );
+        }
+        i += 1;
+    }
+}
+
+// Sections Parser
+// Parses sections and include directives until 'end' or the end of an included file, adding their items to the
+// Program
+// Sections may appear in any order, may be empty, and may be left out, but each may appear only once per file
+// Takes an unsigned integer, a vector of Tokens, the Includes, and the Program parsed so far
+// Returns the index of the 'end' or EOF Token
+// Function panics if syntax errors are found
+fn sections_parser(
+    start_index: usize,
+    tokens: &[Token],
+    includes: &mut Includes,
+    program: &mut Program,
+) -> usize {
+    let mut i: usize = start_index;
+    let num_tokens: usize = tokens.len();
+    let mut curr_token: Token = get_next_token(i, tokens);
+    let mut sections: Vec<(TokenTypes, Location)> = Vec::new();
+
+    while curr_token.token != TokenTypes::END && curr_token.token != TokenTypes::EOF {
+        if curr_token.token == TokenTypes::INCLUDE {
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            assert!(
+                curr_token.token == TokenTypes::STRING,
+                "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected a file name after 'include' instead of '{}'.\n\n",
+                curr_token.location,
+                curr_token.lexeme
+            );
+            include_parser(&curr_token, includes, program);
+            i = increment_i(i, num_tokens);
+            curr_token = get_next_token(i, tokens);
+            continue;
+        }
+
+        if !is_section_token(curr_token.token) {
+            panic!("{}", section_error(i, tokens));
+        }
+        if let Some((_, first)) = sections
+            .iter()
+            .find(|(section, _)| *section == curr_token.token)
+        {
+            panic!(
+                "\n\n; SYNTAX ERROR[E0004]!\n; {}: The '{}' section appears more than once.\n; {}: note: '{}:' first appears here\n; help: sections may come in any order, but each only once, so move these items into the first '{}:' section\n\n",
+                curr_token.location, curr_token.lexeme, first, curr_token.lexeme, curr_token.lexeme
+            );
+        }
+        let section: TokenTypes = curr_token.token;
+        sections.push((section, curr_token.location.clone()));
+
+        i = increment_i(i, num_tokens);
+        curr_token = get_next_token(i, tokens);
+        assert!(
+            curr_token.token == TokenTypes::COLON,
+            "{}",
+            syntax_error(&curr_token, &["':'"])
+        );
+        i = increment_i(i, num_tokens);
+
+        match section {
+            TokenTypes::DATA => {
+                // Only the 2023 edition rewrites the section, so the Tokens are borrowed otherwise
+                let resolved: Cow<[Token]> = if includes.edition == Edition::Edition2023 {
+                    Cow::Owned(legacy_resolver(i, tokens, &mut includes.warnings))
+                } else {
+                    Cow::Borrowed(tokens)
+                };
+                let entries: Vec<DataEntry>;
+                (i, entries) = section_parser(i, &resolved, datadef_parser);
+                for entry in entries {
+                    match entry {
+                        DataEntry::Def(datadef) => program.datadefs.push(datadef),
+                        DataEntry::Const(constant) => program.consts.push(constant),
+                    }
+                }
+            }
+            TokenTypes::DEFINE => {
+                let defines: Vec<Define>;
+                (i, defines) = section_parser(i, tokens, define_parser);
+                program.defines.extend(defines);
+            }
+            TokenTypes::INPUT => {
+                // Constants used as 'read' arguments must be declared before the input section
+                let resolved: Vec<Token> = constant_resolver(tokens, &program.consts);
+                late_constant_checker(i, &resolved);
+                let inputops: Vec<InputOp>;
+                (i, inputops) = section_parser(i, &resolved, inputop_parser);
+                program.inputops.extend(inputops);
+            }
+            TokenTypes::PROCESS => {
+                let processops: Vec<ProcessOp>;
+                (i, processops) = section_parser(i, tokens, processop_parser);
+                program.processops.extend(processops);
+            }
+            _ => {
+                let outputops: Vec<(OutputOp, Location)>;
+                (i, outputops) = section_parser(i, tokens, outputop_parser);
+                program.outputops.extend(outputops);
+            }
+        }
+        curr_token = get_next_token(i, tokens);
+    }
+    return i;
+}
+
+// Program Parser
+// Parses RHS for the program rule of the grammar
+// Takes a vector of Tokens and the Includes
+// Returns the parsed Program
+// Function panics if syntax errors are found
+fn program_parser(tokens: Vec<Token>, includes: &mut Includes) -> Program {
+    let mut i: usize;
+    let num_tokens: usize = tokens.len();
+    let mut program: Program = Program::default();
+
     assert!(
-        curr_token.token == TokenTypes::COLON,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        num_tokens > 0,
+        "\n\n; SYNTAX ERROR[E0002]!\n; Program incomplete!\n\n"
     );
-    i = increment_i(i, num_tokens);
-    (i, prog_output) = special_parser(i, &tokens, outputop_parser, &flag, prog_output);
+    i = sections_parser(0, &tokens, includes, &mut program);
 
-    curr_token = get_next_token(i, &tokens);
+    let end: Token = get_next_token(i, &tokens);
     assert!(
-        curr_token.token == TokenTypes::END,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        i < num_tokens - 1,
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Expected '.' after 'end', the program ends with 'end.'.\n; {}: fix: end.\n\n",
+        end.location,
+        end.location
     );
-    i = increment_i(i, num_tokens);
-    curr_token = get_next_token(i, &tokens);
+    i += 1;
+    let curr_token: Token = get_next_token(i, &tokens);
     assert!(
         curr_token.token == TokenTypes::PERIOD,
-        "\n\n; SYNTAX ERROR!\n; Syntax error at '{}'.\n\n",
-        curr_token.lexeme
+        "{}",
+        syntax_error(&curr_token, &["'.'"])
     );
-    if flag == Flag::Prolog {
-        prog_output.pop();
-        prog_output.push('.');
-    }
 
     assert!(
         i == num_tokens - 1,
-        "\n\n; SYNTAX ERROR!\n; Unexpected characters after 'end.'"
+        "\n\n; SYNTAX ERROR[E0002]!\n; {}: Unexpected characters after 'end.'\n\n",
+        get_next_token(i + 1, &tokens).location
     );
-    return prog_output;
+    return program;
+}
+
+// Source Parser
+// Parses the Tokens of a program, which ends with 'end.', or of a file of sections for 'include', which does not
+// Takes a vector of Tokens without comments, the name of the file they were read from, and the Includes
+// Returns a tuple with the parsed Program and whether it is a whole program
+// Function panics if syntax errors are found
+fn source_parser(tokens: Vec<Token>, file: &str, includes: &mut Includes) -> (Program, bool) {
+    if tokens.iter().any(|token| token.token == TokenTypes::END) {
+        return (program_parser(tokens, includes), true);
+    }
+    let mut tokens: Vec<Token> = tokens;
+    eof_appender(&mut tokens, file);
+    let mut program: Program = Program::default();
+    let i: usize = sections_parser(0, &tokens, includes, &mut program);
+    let curr_token: Token = get_next_token(i, &tokens);
+    assert!(
+        curr_token.token == TokenTypes::EOF,
+        "{}",
+        syntax_error(&curr_token, &["the end of the file"])
+    );
+    return (program, false);
+}
+
+// Format Command
+// Formats a program, or a file of sections for 'include', after checking that it parses
+// The file is rewritten in place, or with '--check' left as it is and reported if it is not formatted
+// Takes the Tokens of the file (comments included), its contents, its name, the Includes, and whether to check only
+// Returns whether the file was already formatted
+// Function panics if lexical or syntax errors are found, or if the file cannot be written
+fn format_command(
+    tokens: Vec<Token>,
+    contents: &str,
+    file: &str,
+    includes: &mut Includes,
+    check: bool,
+) -> bool {
+    source_parser(comment_stripper(tokens.clone()), file, includes);
+    println!("\n; Lexical and Syntax analysis passed.\n");
+
+    let formatted: String = formatter::formatter(&tokens);
+    if formatted == contents {
+        println!("; '{}' is formatted.\n", file);
+        return true;
+    }
+    if check {
+        println!("; '{}' is not formatted.\n", file);
+    } else {
+        fs::write(file, formatted)
+            .expect("\n\n; FILE ERROR[E0019]!\n; Could not write the formatted file!\n\n");
+        println!("; '{}' was formatted.\n", file);
+    }
+    return false;
+}
+
+// Helper function
+// Takes '-W', '-A' or '-D' and returns the lint level it sets
+fn lint_level(param: &str) -> linter::Level {
+    return match param {
+        "-A" => linter::Level::Allow,
+        "-D" => linter::Level::Deny,
+        _ => linter::Level::Warn,
+    };
 }
 
 // Main
-// Receives and checks program parameters, opens and reads the input file, calls the lexer, calls the program parser, and prints the requested output (if any)
+// Receives and checks program parameters, opens and reads the input file, calls the lexer, calls the program parser, calls the semantic checker and the linter, and prints the requested output (if any), or formats or fixes the input file
 // Function panics if any errors are found
 fn main() {
     let prog_params: Vec<String> = env::args().collect();
+    if prog_params.get(1).map(String::as_str) == Some("explain") {
+        assert!(
+            prog_params.len() == 3,
+            "\n\n; Expected one error code after 'explain', e.g. 'explain E0003'!\n\n"
+        );
+        print!("{}", diagnostics::explanation_generator(&prog_params[2]));
+        return;
+    }
     let mut flag: Flag = Flag::None;
     let mut prog_output: String = String::new();
 
-    if prog_params.len() == 1 {
-        panic!("\n\n; No input file provided!\n\n");
-    } else if prog_params.len() == 2 {
-        println!("\n; Processing input file '{}'.\n", prog_params[1]);
-    } else if prog_params.len() == 3 {
+    let mut input_file_name: Option<&String> = None;
+    let mut search_path: Vec<PathBuf> = Vec::new();
+    let mut check: bool = false;
+    let mut levels: linter::Levels = linter::Levels::new();
+    let mut format: MessageFormat = MessageFormat::Human;
+    let mut edition: Edition = Edition::Edition2024;
+    let mut optimize: bool = false;
+
+    // The input file and the flags may be given in any order, e.g. 'main.da -s -I lib', after the 'fmt', 'fix',
+    // 'lint', 'lsp' or 'repl' subcommand if it is given, e.g. 'fmt main.da --check'
+    let mut i: usize = 1;
+    if prog_params.get(1).map(String::as_str) == Some("fmt") {
+        flag = Flag::Format;
+        i = 2;
+    } else if prog_params.get(1).map(String::as_str) == Some("fix") {
+        flag = Flag::Fix;
+        i = 2;
+    } else if prog_params.get(1).map(String::as_str) == Some("lint") {
+        flag = Flag::Lint;
+        i = 2;
+    } else if prog_params.get(1).map(String::as_str) == Some("lsp") {
+        flag = Flag::Server;
+        i = 2;
+    } else if prog_params.get(1).map(String::as_str) == Some("repl") {
+        flag = Flag::Repl;
+        i = 2;
+    }
+    while i < prog_params.len() {
+        let param: &String = &prog_params[i];
+        if param == "-p" || param == "-s" || param == "-r" {
+            assert!(
+                flag == Flag::None,
+                "\n\n; Only one of 'fmt', 'fix', 'lint', 'lsp', 'repl', '--emit', '-s', '-p' and '-r' may be given!\n\n"
+            );
+            if param == "-p" {
+                flag = Flag::Prolog;
+            } else if param == "-r" {
+                flag = Flag::Run;
+            } else {
+                flag = Flag::Scheme;
+            }
+        } else if param == "-I" {
+            i += 1;
+            assert!(
+                i < prog_params.len(),
+                "\n\n; Expected a directory after '-I'!\n\n"
+            );
+            search_path.push(PathBuf::from(&prog_params[i]));
+        } else if let Some(directory) = param.strip_prefix("-I") {
+            search_path.push(PathBuf::from(directory));
+        } else if param == "-W" || param == "-A" || param == "-D" {
+            // Lint levels are applied in order, so '-D warnings -W unused_data' denies every lint but one
+            i += 1;
+            assert!(
+                i < prog_params.len(),
+                "\n\n; Expected a lint after '{}'!\n\n",
+                param
+            );
+            for code in linter::lint_resolver(&prog_params[i]) {
+                levels.insert(code, lint_level(param));
+            }
+        } else if let Some(lint) = ["-W", "-A", "-D"]
+            .iter()
+            .find_map(|prefix| param.strip_prefix(prefix))
+        {
+            for code in linter::lint_resolver(lint) {
+                levels.insert(code, lint_level(&param[..2]));
+            }
+        } else if param == "--emit" {
+            assert!(
+                flag == Flag::None,
+                "\n\n; Only one of 'fmt', 'fix', 'lint', 'lsp', 'repl', '--emit', '-s', '-p' and '-r' may be given!\n\n"
+            );
+            i += 1;
+            flag = match prog_params.get(i).map(String::as_str) {
+                Some("tokens") => Flag::Tokens,
+                Some("ast") => Flag::Ast,
+                _ => panic!("\n\n; Expected 'tokens' or 'ast' after '--emit'!\n\n"),
+            };
+        } else if param == "--edition" {
+            i += 1;
+            edition = match prog_params.get(i).map(String::as_str) {
+                Some("2023") => Edition::Edition2023,
+                Some("2024") => Edition::Edition2024,
+                _ => panic!("\n\n; Expected '2023' or '2024' after '--edition'!\n\n"),
+            };
+        } else if param == "-O" {
+            optimize = true;
+        } else if param == "--message-format" {
+            i += 1;
+            format = match prog_params.get(i).map(String::as_str) {
+                Some("human") => MessageFormat::Human,
+                Some("json") => MessageFormat::Json,
+                _ => panic!("\n\n; Expected 'human' or 'json' after '--message-format'!\n\n"),
+            };
+        } else if param == "--check" {
+            assert!(
+                flag == Flag::Format,
+                "\n\n; '--check' can only be given to 'fmt'!\n\n"
+            );
+            check = true;
+        } else if param.starts_with('-') {
+            panic!("\n\n; Unrecognized input parameter '{}'!\n\n", param);
+        } else {
+            assert!(
+                input_file_name.is_none(),
+                "\n\n; Unrecognized input parameters!\n\n"
+            );
+            input_file_name = Some(param);
+        }
+        i += 1;
+    }
+    // Errors are reported by panicking, and printed as diagnostics with the source line they were found at, or as
+    // JSON lines with '--message-format json'
+    diagnostics::hook_installer(format);
+
+    // The language server talks to the editor over stdin and stdout and reads the programs it sends
+    if flag == Flag::Server {
         assert!(
-            prog_params[2] == "-p" || prog_params[2] == "-s",
-            "\n\n; Unrecognized input parameter '{}'!\n\n",
-            prog_params[2]
+            input_file_name.is_none(),
+            "\n\n; 'lsp' takes no input file!\n\n"
         );
-        if prog_params[2] == "-p" {
-            flag = Flag::Prolog;
-        } else {
-            flag = Flag::Scheme;
+        lsp::server(search_path);
+        return;
+    }
+    // The REPL reads statements from stdin one line at a time
+    if flag == Flag::Repl {
+        assert!(
+            input_file_name.is_none(),
+            "\n\n; 'repl' takes no input file!\n\n"
+        );
+        repl::repl();
+        return;
+    }
+
+    let input_file_name: &String = match input_file_name {
+        Some(name) => name,
+        None => panic!("\n\n; No input file provided!\n\n"),
+    };
+    // Dumps and JSON diagnostics are read by other tools, so nothing else is printed with them
+    let progress: bool =
+        flag != Flag::Tokens && flag != Flag::Ast && format == MessageFormat::Human;
+    if progress {
+        println!("\n; Processing input file '{}'.\n", input_file_name);
+    }
+    // The fixer reads the file again after every fix, and reports the first error it cannot fix
+    if flag == Flag::Fix {
+        let (applied, error) = fixer::fixer(input_file_name, &search_path);
+        if progress {
+            println!("; {} fix(es) applied to '{}'.\n", applied, input_file_name);
         }
-        println!("\n; Processing input file '{}'.\n", prog_params[1]);
-    } else {
-        panic!("\n\n; Unrecognized input parameters!\n\n");
+        if let Some(message) = error {
+            panic!("{}", message);
+        }
+        return;
     }
 
-    let mut input_file: File =
-        File::open(&prog_params[1]).expect("\n\n; FILE ERROR!\n; Could not open the file!\n\n");
+    let mut input_file: File = match File::open(input_file_name) {
+        Ok(file) => file,
+        Err(error) => panic!(
+            "\n\n; FILE ERROR[E0019]!\n; Could not open the file '{}': {}.\n\n",
+            input_file_name, error
+        ),
+    };
 
     let mut contents: String = String::new();
 
     input_file
         .read_to_string(&mut contents)
-        .expect("\n\n; FILE ERROR!\n; The contents of the file could not be read!\n\n");
+        .expect("\n\n; FILE ERROR[E0019]!\n; The contents of the file could not be read!\n\n");
+
+    let tokens: Vec<Token> = lexer(contents.clone(), input_file_name);
+
+    let mut includes: Includes = Includes {
+        search_path,
+        stack: vec![PathBuf::from(input_file_name)],
+        included: Vec::new(),
+        edition,
+        warnings: Vec::new(),
+    };
+    if flag == Flag::Tokens {
+        print!("{}", dump::tokens_dumper(&tokens));
+        return;
+    } else if flag == Flag::Ast {
+        let (program, _) = source_parser(comment_stripper(tokens), input_file_name, &mut includes);
+        println!("{}", dump::program_dumper(&program));
+        return;
+    }
+    if flag == Flag::Format {
+        if !format_command(tokens, &contents, input_file_name, &mut includes, check) && check {
+            std::process::exit(1);
+        }
+        return;
+    }
+    let mut program: Program = program_parser(comment_stripper(tokens), &mut includes);
 
-    let tokens: Vec<Token> = lexer(contents);
+    if progress {
+        println!("\n; Lexical and Syntax analysis passed.\n");
+    }
 
-    prog_output = program_parser(tokens, flag, prog_output);
+    dataflow::dataflow_sorter(&mut program);
+    checker::semantic_checker(&program);
 
-    println!("\n; Lexical and Syntax analysis passed.\n");
+    // Warnings about deprecated syntax are found by the parser, the others by the linter
+    let mut warnings: Vec<linter::Warning> = includes.warnings;
+    warnings.extend(linter::linter(&program));
+    let warnings: usize = linter::warnings_reporter(&warnings, &levels, format);
+    if flag == Flag::Lint {
+        if progress {
+            println!("; {} warning(s) found.\n", warnings);
+        }
+        return;
+    }
 
-    println!("{prog_output}\n");
+    if flag == Flag::Prolog || flag == Flag::Scheme {
+        // The optimized program is checked again, since only the user functions it still calls are generated
+        // Without '-O' the program is translated as written
+        let program: Program = if optimize {
+            optimizer::optimizer(program)
+        } else {
+            program
+        };
+        let signatures: checker::Signatures = checker::semantic_checker(&program);
+        if flag == Flag::Prolog {
+            prog_output = prolog::prolog_generator(&program);
+        } else {
+            prog_output = scheme::scheme_generator(&program, &signatures);
+        }
+    } else if flag == Flag::Run {
+        interpreter::interpreter(&program);
+        return;
+    }
+
+    if progress || !prog_output.is_empty() {
+        println!("{prog_output}\n");
+    }
 }
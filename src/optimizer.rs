@@ -0,0 +1,251 @@
+// Optimizer for DA programs
+// Rewrites a checked Program before the Scheme and Prolog generators translate it, so that the generated code
+// loads and computes only what the output section needs:
+// an input op that reads what an earlier one reads, and a process op that makes a call an earlier one makes, take
+// the earlier result instead; process ops and input ops whose results never reach an output op are removed; and a
+// CSV file that several vectors are read from is loaded once as a table, whose columns are then taken one by one
+// Statistics functions and user functions have no side effects, so none of this changes what the program prints
+
+use crate::ast::{
+    identifiers, output_identifiers, substitute, DataDef, DataType, Expr, InputOp, ProcessOp,
+    Program, Source,
+};
+
+// Helper function
+// Takes an expression, a call, and the identifier the result of the call is bound to
+// Returns a copy of the expression where every occurrence of the call is replaced by the identifier
+fn call_replacer(expr: &Expr, call: &Expr, id: &String) -> Expr {
+    if expr == call {
+        return Expr::Id(id.clone());
+    }
+    match expr {
+        Expr::Call(func, args) => Expr::Call(
+            func.clone(),
+            args.iter()
+                .map(|arg| call_replacer(arg, call, id))
+                .collect(),
+        ),
+        Expr::Binary(op, lhs, rhs) => Expr::Binary(
+            *op,
+            Box::new(call_replacer(lhs, call, id)),
+            Box::new(call_replacer(rhs, call, id)),
+        ),
+        Expr::Negate(operand) => Expr::Negate(Box::new(call_replacer(operand, call, id))),
+        Expr::Filter(vector, comparison, threshold) => Expr::Filter(
+            Box::new(call_replacer(vector, call, id)),
+            comparison.clone(),
+            Box::new(call_replacer(threshold, call, id)),
+        ),
+        Expr::Column(table, column) => {
+            Expr::Column(Box::new(call_replacer(table, call, id)), column.clone())
+        }
+        _ => expr.clone(),
+    }
+}
+
+// Duplicate Reads Merger
+// Replaces every input op that reads the same as an earlier one by a process op that copies the earlier value
+// Takes the Program to rewrite
+fn duplicate_reads_merger(program: &mut Program) {
+    let mut inputops: Vec<InputOp> = Vec::new();
+    let mut copies: Vec<ProcessOp> = Vec::new();
+    for inputop in program.inputops.drain(..) {
+        match inputops.iter().find(|other| other.source == inputop.source) {
+            Some(other) => copies.push(ProcessOp {
+                ids: vec![inputop.id],
+                expr: Expr::Id(other.id.clone()),
+                location: inputop.location,
+            }),
+            None => inputops.push(inputop),
+        }
+    }
+    program.inputops = inputops;
+    copies.append(&mut program.processops);
+    program.processops = copies;
+}
+
+// Duplicate Calls Merger
+// Replaces every call that an earlier process op makes as a whole by the identifier the earlier op binds it to
+// Copies are looked through first, so 'mean(y)' is found to repeat 'mean(x)' after 'y = x'
+// A call with several results can only be made as a whole, so a process op repeating one copies each result instead
+// Takes the Program to rewrite
+fn duplicate_calls_merger(program: &mut Program) {
+    let mut processops: Vec<ProcessOp> = Vec::new();
+    let mut calls: Vec<(Expr, Vec<String>)> = Vec::new();
+    let mut copies: Vec<String> = Vec::new();
+    let mut originals: Vec<Expr> = Vec::new();
+    for processop in program.processops.drain(..) {
+        let mut expr: Expr = substitute(&processop.expr, &copies, &originals);
+        if let (Expr::Id(_), [id]) = (&expr, processop.ids.as_slice()) {
+            copies.push(id.clone());
+            originals.push(expr.clone());
+        }
+        for (call, ids) in &calls {
+            if let [id] = ids.as_slice() {
+                expr = call_replacer(&expr, call, id);
+            }
+        }
+        if let Expr::Call(..) = expr {
+            if let Some((_, ids)) = calls.iter().find(|(call, _)| *call == expr) {
+                for (id, earlier) in processop.ids.iter().zip(ids) {
+                    processops.push(ProcessOp {
+                        ids: vec![id.clone()],
+                        expr: Expr::Id(earlier.clone()),
+                        location: processop.location.clone(),
+                    });
+                }
+                continue;
+            }
+            calls.push((expr.clone(), processop.ids.clone()));
+        }
+        processops.push(ProcessOp {
+            ids: processop.ids,
+            expr,
+            location: processop.location,
+        });
+    }
+    program.processops = processops;
+}
+
+// Dead Code Eliminator
+// Removes the process ops and input ops whose results no output op uses, directly or through other process ops
+// Initialisers of the data section are cheap and always kept, together with what they use
+// Takes the Program to rewrite
+fn dead_code_eliminator(program: &mut Program) {
+    let mut live: Vec<String> = Vec::new();
+    for (outputop, _) in &program.outputops {
+        output_identifiers(outputop, &mut live);
+    }
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            identifiers(init, &mut live);
+        }
+    }
+
+    // A process op may use the results of any other, so the live ones are found until no more are
+    let mut kept: Vec<bool> = vec![false; program.processops.len()];
+    let mut changed: bool = true;
+    while changed {
+        changed = false;
+        for (i, processop) in program.processops.iter().enumerate() {
+            if !kept[i] && processop.ids.iter().any(|id| live.contains(id)) {
+                kept[i] = true;
+                identifiers(&processop.expr, &mut live);
+                changed = true;
+            }
+        }
+    }
+
+    let mut kept = kept.into_iter();
+    program.processops.retain(|_| kept.next().unwrap());
+    program
+        .inputops
+        .retain(|inputop| live.contains(&inputop.id));
+}
+
+// CSV Reads Merger
+// Replaces the reads of several columns of the same CSV file by one read of the whole file as a table, declared
+// as 'csv0', 'csv1', ..., names DA identifiers can never take since they cannot contain digits, and process ops
+// that take the columns out of it
+// Files read with a delimiter are left alone, since tables are always read as CSV
+// Takes the Program to rewrite
+fn csv_reads_merger(program: &mut Program) {
+    let shared = |file: &String, header: &bool| -> bool {
+        let reads: usize = program
+            .inputops
+            .iter()
+            .filter(|inputop| {
+                matches!(&inputop.source, Source::Csv { file: other_file, header: other_header, delimiter: None, .. }
+                    if other_file == file && other_header == header)
+            })
+            .count();
+        return reads > 1;
+    };
+    let mut merged: Vec<(String, bool, String)> = Vec::new();
+    let mut inputops: Vec<InputOp> = Vec::new();
+    let mut datadefs: Vec<DataDef> = Vec::new();
+    let mut columns: Vec<ProcessOp> = Vec::new();
+    for inputop in &program.inputops {
+        let Source::Csv {
+            file,
+            header,
+            column,
+            delimiter: None,
+        } = &inputop.source
+        else {
+            continue;
+        };
+        if !shared(file, header) {
+            continue;
+        }
+        let table: String = match merged
+            .iter()
+            .find(|(other_file, other_header, _)| other_file == file && other_header == header)
+        {
+            Some((_, _, table)) => table.clone(),
+            None => {
+                let table: String = format!("csv{}", merged.len());
+                merged.push((file.clone(), *header, table.clone()));
+                datadefs.push(DataDef {
+                    id: table.clone(),
+                    data_type: DataType::Table,
+                    init: None,
+                    location: inputop.location.clone(),
+                });
+                table
+            }
+        };
+        columns.push(ProcessOp {
+            ids: vec![inputop.id.clone()],
+            expr: Expr::Column(Box::new(Expr::Id(table)), column.clone()),
+            location: inputop.location.clone(),
+        });
+    }
+
+    // Each table is read where the first of its columns was
+    let mut tables: Vec<String> = Vec::new();
+    for inputop in program.inputops.drain(..) {
+        let table = match &inputop.source {
+            Source::Csv {
+                file,
+                header,
+                delimiter: None,
+                ..
+            } => merged
+                .iter()
+                .find(|(other_file, other_header, _)| other_file == file && other_header == header),
+            _ => None,
+        };
+        match table {
+            Some((file, header, table)) => {
+                if !tables.contains(table) {
+                    tables.push(table.clone());
+                    inputops.push(InputOp {
+                        id: table.clone(),
+                        source: Source::Table {
+                            file: file.clone(),
+                            header: *header,
+                        },
+                        location: inputop.location,
+                    });
+                }
+            }
+            None => inputops.push(inputop),
+        }
+    }
+    program.inputops = inputops;
+    program.datadefs.append(&mut datadefs);
+    columns.append(&mut program.processops);
+    program.processops = columns;
+}
+
+// Optimizer
+// Takes a Program that passed the semantic checker
+// Returns the Program with duplicate reads and calls merged, dead code removed, and each shared CSV file read once
+pub fn optimizer(mut program: Program) -> Program {
+    duplicate_reads_merger(&mut program);
+    duplicate_calls_merger(&mut program);
+    dead_code_eliminator(&mut program);
+    csv_reads_merger(&mut program);
+    return program;
+}
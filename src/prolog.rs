@@ -0,0 +1,395 @@
+// Prolog code generator
+// Translates a checked Program into the body of a single 'main' clause
+
+use crate::ast::{
+    identifiers, inline_calls, output_identifiers, substitute, Column, DataType, Define, Expr,
+    OutputOp, PrintItem, Program, Source,
+};
+use crate::checker::expression_type;
+use std::collections::HashMap;
+
+// Helper function
+// Takes the requested target of a goal, if any, and the counter for fresh variables
+// Returns the target or else a fresh variable
+fn result_variable(target: Option<&String>, temps: &mut usize) -> String {
+    match target {
+        Some(target) => return target.clone(),
+        None => {
+            *temps += 1;
+            return format!("T{}", *temps - 1);
+        }
+    }
+}
+
+// Helper function
+// Takes an identifier and the variables the constants are looked up into, and returns the variable of the
+// identifier if it is a constant
+fn constant_term(id: &String, constants: &HashMap<String, String>) -> String {
+    match constants.get(id) {
+        Some(variable) => return variable.clone(),
+        None => return id.clone(),
+    }
+}
+
+// Helper function
+// Takes an Expr and its generated term and parenthesises the term if it is an arithmetic operation
+fn operand(expr: &Expr, term: String) -> String {
+    if let Expr::Binary(..) = expr {
+        return format!("({term})");
+    }
+    return term;
+}
+
+// Expression Generator
+// Takes an Expr, the types of the identifiers in scope, the user functions, the goals generated so far, a counter
+// for fresh variables, and the variable the value should be bound to, if any
+// Every call and vector operation is hoisted into its own goal whose result is bound to a fresh variable
+// (or the target), while arithmetic on numbers stays a term for 'is'
+// Element-wise operations use maplist/include with yall lambdas over 'X', 'Y' and 'Z'
+// Returns the term for the expression
+fn expression_generator(
+    expr: &Expr,
+    scope: &HashMap<String, DataType>,
+    functions: &HashMap<String, &Define>,
+    goals: &mut Vec<String>,
+    temps: &mut usize,
+    target: Option<&String>,
+) -> String {
+    match expr {
+        Expr::Num(num) => return num.clone(),
+        Expr::Id(id) => return id.clone(),
+        Expr::Call(func, args) => {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| expression_generator(arg, scope, functions, goals, temps, None))
+                .collect();
+            let result: String = result_variable(target, temps);
+            if func == "abs" || func == "log" {
+                goals.push(format!(
+                    "maplist([X, Y]>>(Y is {func}(X)), {}, {result})",
+                    args[0]
+                ));
+            } else if func == "concat" {
+                goals.push(format!("append({}, {}, {result})", args[0], args[1]));
+            } else {
+                goals.push(format!("{func}({}, {result})", args.join(", ")));
+            }
+            return result;
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs_type: DataType = expression_type(lhs, scope, functions);
+            let rhs_type: DataType = expression_type(rhs, scope, functions);
+            let lhs_term: String = expression_generator(lhs, scope, functions, goals, temps, None);
+            let rhs_term: String = expression_generator(rhs, scope, functions, goals, temps, None);
+            let lhs_term: String = operand(lhs, lhs_term);
+            let rhs_term: String = operand(rhs, rhs_term);
+            if lhs_type == DataType::Number && rhs_type == DataType::Number {
+                return format!("{lhs_term} {op} {rhs_term}");
+            }
+            let result: String = result_variable(target, temps);
+            if lhs_type == DataType::Vector && rhs_type == DataType::Vector {
+                goals.push(format!(
+                    "maplist([X, Y, Z]>>(Z is X {op} Y), {lhs_term}, {rhs_term}, {result})"
+                ));
+            } else if lhs_type == DataType::Vector {
+                goals.push(format!(
+                    "maplist([X, Z]>>(Z is X {op} {rhs_term}), {lhs_term}, {result})"
+                ));
+            } else {
+                goals.push(format!(
+                    "maplist([X, Z]>>(Z is {lhs_term} {op} X), {rhs_term}, {result})"
+                ));
+            }
+            return result;
+        }
+        Expr::Negate(inner) => {
+            let inner_type: DataType = expression_type(inner, scope, functions);
+            let term: String = expression_generator(inner, scope, functions, goals, temps, None);
+            // The operand is always parenthesised, since '--1' or '--m' would not parse
+            if inner_type == DataType::Number {
+                return format!("-({term})");
+            }
+            let result: String = result_variable(target, temps);
+            goals.push(format!("maplist([X, Z]>>(Z is -X), {term}, {result})"));
+            return result;
+        }
+        Expr::Filter(vector, comparison, threshold) => {
+            let vector: String = expression_generator(vector, scope, functions, goals, temps, None);
+            let term: String =
+                expression_generator(threshold, scope, functions, goals, temps, None);
+            let term: String = operand(threshold, term);
+            let comparison: &str = match comparison.as_str() {
+                "==" => "=:=",
+                "!=" => "=\\=",
+                "<=" => "=<",
+                other => other,
+            };
+            let result: String = result_variable(target, temps);
+            goals.push(format!(
+                "include([X]>>(X {comparison} {term}), {vector}, {result})"
+            ));
+            return result;
+        }
+        Expr::Column(table, column) => {
+            let table: String = expression_generator(table, scope, functions, goals, temps, None);
+            let column: String = match column {
+                Column::Name(name) => format!("\"{name}\""),
+                Column::Index(index) => index.to_string(),
+            };
+            let result: String = result_variable(target, temps);
+            goals.push(format!("table_column({table}, {column}, {result})"));
+            return result;
+        }
+    }
+}
+
+// PrintItems Generator
+// Builds the format string and arguments of a format/2 or format/3 call for a list of PrintItems
+// Strings become part of the format string and identifiers become its arguments, separated by the
+// given separator; with a separator, vectors are first joined into one atom with the same separator
+// Takes the PrintItems, the separator, the types of the identifiers, the variables of the constants, the goals,
+// and the fresh variable counter
+// Returns a tuple with the format string and the arguments
+fn printitems_generator(
+    items: &[PrintItem],
+    separator: &str,
+    scope: &HashMap<String, DataType>,
+    constants: &HashMap<String, String>,
+    goals: &mut Vec<String>,
+    temps: &mut usize,
+) -> (String, Vec<String>) {
+    let mut format_string: String = String::new();
+    let mut args: Vec<String> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            format_string.push_str(separator);
+        }
+        match item {
+            PrintItem::Str(str) => {
+                format_string.push_str(&str[1..str.len() - 1].replace('~', "~~"));
+            }
+            PrintItem::Id(id, precision) => {
+                match precision {
+                    Some(precision) => format_string.push_str(&format!("~{precision}f")),
+                    None => format_string.push_str("~w"),
+                }
+                if !separator.is_empty() && scope.get(id) == Some(&DataType::Vector) {
+                    let joined: String = result_variable(None, temps);
+                    goals.push(format!("atomic_list_concat({id}, '{separator}', {joined})"));
+                    args.push(joined);
+                } else {
+                    args.push(constant_term(id, constants));
+                }
+            }
+        }
+    }
+    return (format_string, args);
+}
+
+// Assignment Generator
+// Generates the goals that bind an identifier to the value of an expression
+// Takes the identifier, the Expr, the types of the identifiers in scope, the user functions, the goals, and the
+// fresh variable counter
+fn assignment_generator(
+    id: &String,
+    expr: &Expr,
+    scope: &HashMap<String, DataType>,
+    functions: &HashMap<String, &Define>,
+    goals: &mut Vec<String>,
+    temps: &mut usize,
+) {
+    let term: String = expression_generator(expr, scope, functions, goals, temps, Some(id));
+    if term != *id {
+        if let Expr::Id(_) = expr {
+            goals.push(format!("{id} = {term}"));
+        } else {
+            goals.push(format!("{id} is {term}"));
+        }
+    }
+}
+
+// Prolog Generator
+// User functions are inlined at every call, since the main clause binds results positionally
+// Constants become 'da_const/2' facts, and the main clause starts by looking up the ones it uses into fresh
+// variables, e.g. 'da_const(cutoff, T0)'
+// Takes a Program
+// Returns a String with the Prolog code
+pub fn prolog_generator(program: &Program) -> String {
+    let mut prog_output: String = String::new();
+    let mut goals: Vec<String> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut temps: usize = 0;
+
+    let mut declared: HashMap<String, DataType> = HashMap::new();
+    for datadef in &program.datadefs {
+        declared.insert(datadef.id.clone(), datadef.data_type);
+    }
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        functions.insert(define.name.clone(), define);
+    }
+
+    // Constants given to 'read' are resolved by the parser, so only the ones used here are looked up
+    let mut used: Vec<String> = Vec::new();
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            identifiers(init, &mut used);
+        }
+    }
+    for processop in &program.processops {
+        identifiers(&processop.expr, &mut used);
+    }
+    for (outputop, _) in &program.outputops {
+        output_identifiers(outputop, &mut used);
+    }
+    let mut const_ids: Vec<String> = Vec::new();
+    let mut const_values: Vec<Expr> = Vec::new();
+    let mut constants: HashMap<String, String> = HashMap::new();
+    for constant in &program.consts {
+        prog_output.push_str(format!("da_const({}, {}).\n", constant.id, constant.value).as_str());
+        if used.contains(&constant.id) {
+            let variable: String = result_variable(None, &mut temps);
+            goals.push(format!("da_const({}, {variable})", constant.id));
+            const_ids.push(constant.id.clone());
+            const_values.push(Expr::Id(variable.clone()));
+            // Only numeric constants are used in expressions
+            declared.insert(variable.clone(), DataType::Number);
+            constants.insert(constant.id.clone(), variable);
+        }
+    }
+
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            let expr: Expr = substitute(&inline_calls(init, &functions), &const_ids, &const_values);
+            assignment_generator(
+                &datadef.id,
+                &expr,
+                &declared,
+                &functions,
+                &mut goals,
+                &mut temps,
+            );
+        }
+    }
+
+    for inputop in &program.inputops {
+        let id: &String = &inputop.id;
+        match &inputop.source {
+            Source::Csv {
+                file,
+                header,
+                column,
+                delimiter: None,
+            } => goals.push(format!(
+                "load_data_column({file}, {header}, {column}, {id})"
+            )),
+            Source::Csv {
+                file,
+                header,
+                column,
+                delimiter: Some(delimiter),
+            } => goals.push(format!(
+                "load_delimited_column({file}, {header}, {column}, {delimiter}, {id})"
+            )),
+            Source::Json {
+                file,
+                field: Some(field),
+            } => goals.push(format!("load_json_field({file}, {field}, {id})")),
+            Source::Json { file, field: None } => {
+                goals.push(format!("load_json_array({file}, {id})"))
+            }
+            Source::Lines { file } => goals.push(format!("load_lines({file}, {id})")),
+            Source::Table { file, header } => {
+                goals.push(format!("load_table({file}, {header}, {id})"))
+            }
+        }
+    }
+
+    for processop in &program.processops {
+        let expr: Expr = substitute(
+            &inline_calls(&processop.expr, &functions),
+            &const_ids,
+            &const_values,
+        );
+        if processop.ids.len() > 1 {
+            // A call with several results binds them all as its trailing output arguments,
+            // e.g. 'regression(x, y, a, b, rsq)'
+            let targets: String = processop.ids.join(", ");
+            expression_generator(
+                &expr,
+                &declared,
+                &functions,
+                &mut goals,
+                &mut temps,
+                Some(&targets),
+            );
+            continue;
+        }
+        assignment_generator(
+            &processop.ids[0],
+            &expr,
+            &declared,
+            &functions,
+            &mut goals,
+            &mut temps,
+        );
+    }
+
+    for (outputop, _) in &program.outputops {
+        match outputop {
+            OutputOp::Str(str_or_id) | OutputOp::Id(str_or_id) => {
+                goals.push(format!("writeIn({})", constant_term(str_or_id, &constants)));
+            }
+            OutputOp::Print(items) => {
+                let (format_string, args) =
+                    printitems_generator(items, "", &declared, &constants, &mut goals, &mut temps);
+                goals.push(format!(
+                    "format(\"{format_string}~n\", [{}])",
+                    args.join(", ")
+                ));
+            }
+            OutputOp::Write {
+                file,
+                items,
+                append,
+            } => {
+                let mode: &str = if *append { "append" } else { "write" };
+                let (format_string, args) =
+                    printitems_generator(items, ",", &declared, &constants, &mut goals, &mut temps);
+                let stream: String = result_variable(None, &mut temps);
+                goals.push(format!("open({file}, {mode}, {stream})"));
+                goals.push(format!(
+                    "format({stream}, \"{format_string}~n\", [{}])",
+                    args.join(", ")
+                ));
+                goals.push(format!("close({stream})"));
+            }
+            OutputOp::Plot { x, y, file } => {
+                skipped.push(format!("plot({x}, {y}, {file})"));
+            }
+            OutputOp::Histogram { values, file } => {
+                skipped.push(format!("histogram({values}, {file})"));
+            }
+        }
+    }
+
+    // Prolog has no standard plotting library, so charts are only mentioned in a comment
+    for statement in &skipped {
+        prog_output.push_str(
+            format!("% {statement} is not supported by the Prolog backend and was skipped\n")
+                .as_str(),
+        );
+    }
+    // A program without inputs, process ops, or output ops still defines main
+    if goals.is_empty() {
+        goals.push(String::from("true"));
+    }
+    prog_output.push_str("main :-");
+    for goal in &goals {
+        prog_output.push_str(format!("\n   {goal},").as_str());
+    }
+    prog_output.pop();
+    prog_output.push('.');
+
+    return prog_output;
+}
@@ -0,0 +1,276 @@
+// Interactive REPL for DA programs
+// Reads one statement per line: a data entry ('x : vector'), a user function ('cv(v) = stddev(v) / mean(v)'), an
+// input op ('x = read(...)'), a process op ('a = mean(x)') or an output op ('print(...)', or an identifier to show
+// its value), and runs it with the interpreter right away
+// The statements accepted so far form a program without sections, which every new statement is checked against
+// and which ':show scheme' and ':show prolog' translate
+// Errors are reported by panicking, like everywhere else, so every statement runs under catch_unwind and an error
+// only rejects the statement it was found in
+
+use crate::ast::{is_numeric, DataType, Define, Expr, Program};
+use crate::checker::{expression_type, semantic_checker, type_name};
+use crate::diagnostics::{diagnostic_parser, diagnostic_renderer, file_source, panic_message};
+use crate::interpreter::{
+    expression_evaluator, input_evaluator, outputop_evaluator, results_evaluator, Value,
+};
+use crate::{
+    comment_stripper, constant_resolver, datadef_parser, define_parser, eof_appender,
+    expression_parser, get_next_token, inputop_parser, lexer, outputop_parser, processop_parser,
+    prolog, scheme, syntax_error, DataEntry, Token, TokenTypes,
+};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+const HELP: &str =
+    "; Enter one statement per line, e.g. 'x : vector', 'x = read(\"file.csv\", false, 0)',
+; 'a = mean(x)' or 'print(\"a = \", a:3)'. An identifier on its own shows its value.
+; :type <expression>   show the type of an expression
+; :show scheme         show the Scheme translation of the statements so far
+; :show prolog         show the Prolog translation of the statements so far
+; :help                show this help
+; :quit                leave the REPL";
+
+// Helper function
+// Takes a line and the number of the statement it holds, and returns its Tokens without comments, ended by an
+// EOF Token so that the item parsers can look past the end of the statement
+// The statements of a session are numbered like the lines of a file, so errors are located at 'repl:3:5'
+fn statement_lexer(line: &str, number: usize) -> Vec<Token> {
+    let mut tokens: Vec<Token> = comment_stripper(lexer(String::from(line), "repl"));
+    eof_appender(&mut tokens, "repl");
+    for token in tokens.iter_mut() {
+        token.location.line = number;
+    }
+    return tokens;
+}
+
+// Helper function
+// Takes the Tokens of a statement and the index the statement's parser stopped at
+// Function panics if the statement goes on after that index
+fn end_checker(i: usize, tokens: &[Token]) {
+    let curr_token: Token = get_next_token(i, tokens);
+    assert!(
+        curr_token.token == TokenTypes::EOF,
+        "{}",
+        syntax_error(&curr_token, &["the end of the statement"])
+    );
+}
+
+// Statement Parser
+// Parses one statement and adds it to the program, in the section it belongs to
+// Takes a line, the number of the statement it holds, and the Program of the statements before it
+// Returns the kind of the statement: CONST, DATA, DEFINE, INPUT, PROCESS or OUTPUT
+// Function panics if lexical or syntax errors are found
+fn statement_parser(line: &str, number: usize, program: &mut Program) -> TokenTypes {
+    let tokens: Vec<Token> = statement_lexer(line, number);
+    let first: TokenTypes = tokens[0].token;
+    let second: TokenTypes = tokens.get(1).map_or(TokenTypes::EOF, |token| token.token);
+    let third: TokenTypes = tokens.get(2).map_or(TokenTypes::EOF, |token| token.token);
+    let i: usize;
+
+    if first == TokenTypes::CONST || (first == TokenTypes::ID && second == TokenTypes::COLON) {
+        let entry: DataEntry;
+        (i, entry) = datadef_parser(0, &tokens);
+        end_checker(i, &tokens);
+        match entry {
+            DataEntry::Def(datadef) => program.datadefs.push(datadef),
+            DataEntry::Const(constant) => {
+                program.consts.push(constant);
+                return TokenTypes::CONST;
+            }
+        }
+        return TokenTypes::DATA;
+    } else if first == TokenTypes::ID && second == TokenTypes::LPAREN {
+        let define: Define;
+        (i, define) = define_parser(0, &tokens);
+        end_checker(i, &tokens);
+        program.defines.push(define);
+        return TokenTypes::DEFINE;
+    } else if first == TokenTypes::ID
+        && second == TokenTypes::ASSIGN
+        && matches!(
+            third,
+            TokenTypes::READ | TokenTypes::READJSON | TokenTypes::READLINES | TokenTypes::READTABLE
+        )
+    {
+        let tokens: Vec<Token> = constant_resolver(&tokens, &program.consts);
+        let (i, inputop) = inputop_parser(0, &tokens);
+        end_checker(i, &tokens);
+        program.inputops.push(inputop);
+        return TokenTypes::INPUT;
+    } else if first == TokenTypes::ID
+        && (second == TokenTypes::ASSIGN || second == TokenTypes::COMMA)
+    {
+        let (i, processop) = processop_parser(0, &tokens);
+        end_checker(i, &tokens);
+        program.processops.push(processop);
+        return TokenTypes::PROCESS;
+    }
+    let (i, outputop) = outputop_parser(0, &tokens);
+    end_checker(i, &tokens);
+    program.outputops.push(outputop);
+    return TokenTypes::OUTPUT;
+}
+
+// Statement Evaluator
+// Runs the last statement added to a program of the given kind, updating the values computed so far
+// Takes the kind of the statement, the Program, and the values computed so far
+// Function panics if runtime errors are found
+fn statement_evaluator(kind: TokenTypes, program: &Program, env: &mut HashMap<String, Value>) {
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        functions.insert(define.name.clone(), define);
+    }
+    match kind {
+        TokenTypes::CONST => {
+            let constant = program.consts.last().unwrap();
+            if is_numeric(constant) {
+                env.insert(
+                    constant.id.clone(),
+                    Value::Number(constant.value.parse().unwrap()),
+                );
+            }
+        }
+        TokenTypes::DATA => {
+            let datadef = program.datadefs.last().unwrap();
+            if let Some(init) = &datadef.init {
+                let value: Value = expression_evaluator(init, env, &functions);
+                env.insert(datadef.id.clone(), value);
+            }
+        }
+        TokenTypes::INPUT => {
+            let inputop = program.inputops.last().unwrap();
+            env.insert(inputop.id.clone(), input_evaluator(&inputop.source));
+        }
+        TokenTypes::PROCESS => {
+            let processop = program.processops.last().unwrap();
+            let values: Vec<Value> = results_evaluator(&processop.expr, env, &functions);
+            for (id, value) in processop.ids.iter().zip(values) {
+                env.insert(id.clone(), value);
+            }
+        }
+        TokenTypes::OUTPUT => outputop_evaluator(&program.outputops.last().unwrap().0, env),
+        _ => {}
+    }
+}
+
+// Helper function
+// Takes the statements accepted so far
+// Returns the Program they form
+// Function panics if one of them no longer parses, which cannot happen since each parsed when it was accepted
+fn session_parser(statements: &[String]) -> Program {
+    let mut program: Program = Program::default();
+    for (i, statement) in statements.iter().enumerate() {
+        statement_parser(statement, i + 1, &mut program);
+    }
+    return program;
+}
+
+// Type Command
+// Takes an expression, the number the next statement would have, and the Program of the statements so far
+// Returns the type of the expression
+// Function panics if the expression does not parse or does not type check
+fn type_command(text: &str, number: usize, program: &Program) -> &'static str {
+    let tokens: Vec<Token> = statement_lexer(text, number);
+    let (i, expr): (usize, Expr) = expression_parser(0, &tokens);
+    end_checker(i, &tokens);
+
+    let mut scope: HashMap<String, DataType> = HashMap::new();
+    for constant in &program.consts {
+        if is_numeric(constant) {
+            scope.insert(constant.id.clone(), DataType::Number);
+        }
+    }
+    for datadef in &program.datadefs {
+        scope.insert(datadef.id.clone(), datadef.data_type);
+    }
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        functions.insert(define.name.clone(), define);
+    }
+    return type_name(expression_type(&expr, &scope, &functions));
+}
+
+// Helper function
+// Takes the payload of a caught panic, the statements accepted so far, and the text of the rejected one
+// Prints the error as a diagnostic, with the statement it was found in as its source line
+fn error_printer(payload: Box<dyn std::any::Any + Send>, statements: &[String], rejected: &str) {
+    let message: String = panic_message(&*payload);
+    let source = |file: &str| -> Option<String> {
+        if file != "repl" {
+            return file_source(file);
+        }
+        let mut lines: Vec<&str> = statements.iter().map(String::as_str).collect();
+        lines.push(rejected);
+        return Some(lines.join("\n"));
+    };
+    match diagnostic_parser(&message) {
+        Some(diagnostic) => print!("{}", diagnostic_renderer(&diagnostic, &source)),
+        None => println!("{}", message.trim()),
+    }
+}
+
+// REPL
+// Reads statements and commands from stdin until ':quit' or the end of the input, running each statement as it
+// is entered
+pub fn repl() {
+    // Errors are printed by error_printer, so the default panic messages are not
+    panic::set_hook(Box::new(|_| {}));
+    let mut statements: Vec<String> = Vec::new();
+    let mut env: HashMap<String, Value> = HashMap::new();
+    let mut reader = std::io::stdin().lock();
+
+    println!("; DA REPL, enter ':help' for help.");
+    loop {
+        print!("da> ");
+        let _ = std::io::stdout().flush();
+        let mut line: String = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line: &str = line.trim();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            if line.is_empty() || line.starts_with('#') {
+                return true;
+            } else if line == ":quit" || line == ":q" {
+                return false;
+            } else if line == ":help" {
+                println!("{HELP}");
+            } else if let Some(text) = line.strip_prefix(":type") {
+                let program: Program = session_parser(&statements);
+                println!(
+                    "{}",
+                    type_command(text.trim(), statements.len() + 1, &program)
+                );
+            } else if line == ":show scheme" {
+                let program: Program = session_parser(&statements);
+                let signatures = semantic_checker(&program);
+                print!("{}", scheme::scheme_generator(&program, &signatures));
+            } else if line == ":show prolog" {
+                let program: Program = session_parser(&statements);
+                println!("{}", prolog::prolog_generator(&program));
+            } else if line.starts_with(':') {
+                println!("; Unknown command '{line}', enter ':help' for help.");
+            } else {
+                // The statement is checked together with the statements before it, then run on its own
+                let mut program: Program = session_parser(&statements);
+                let kind: TokenTypes = statement_parser(line, statements.len() + 1, &mut program);
+                semantic_checker(&program);
+                statement_evaluator(kind, &program, &mut env);
+                statements.push(String::from(line));
+            }
+            return true;
+        }));
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(payload) => {
+                // ':type' lexes the expression after the command on its own
+                let rejected: &str = line.strip_prefix(":type").map_or(line, str::trim);
+                error_printer(payload, &statements, rejected);
+            }
+        }
+    }
+}
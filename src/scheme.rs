@@ -0,0 +1,294 @@
+// Scheme code generator
+// Translates a checked Program into Scheme definitions and display calls
+
+use crate::ast::{
+    is_numeric, Column, DataType, Define, Expr, OutputOp, PrintItem, Program, Source,
+};
+use crate::checker::{expression_type, Signatures};
+use std::collections::HashMap;
+
+// Helper function
+// Takes a bool and returns the matching Scheme boolean literal
+fn boolean(value: bool) -> &'static str {
+    return if value { "#t" } else { "#f" };
+}
+
+// Expression Generator
+// Takes an Expr, the types of the identifiers in scope, and the user functions
+// Element-wise operations on vectors are generated with 'map' and 'filter' over a lambda on 'e0', and a number
+// they use is bound to 's0', names DA identifiers can never take since they cannot contain digits
+// Returns the expression as a Scheme expression
+fn expression_generator(
+    expr: &Expr,
+    scope: &HashMap<String, DataType>,
+    functions: &HashMap<String, &Define>,
+) -> String {
+    match expr {
+        Expr::Num(num) => return num.clone(),
+        Expr::Id(id) => return id.clone(),
+        Expr::Call(func, args) => {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| expression_generator(arg, scope, functions))
+                .collect();
+            if func == "abs" || func == "log" {
+                return format!("(map {func} {})", args[0]);
+            } else if func == "slice" {
+                return format!(
+                    "(take (drop {} {}) (- {} {}))",
+                    args[0], args[1], args[2], args[1]
+                );
+            } else if func == "concat" {
+                return format!("(append {} {})", args[0], args[1]);
+            }
+            return format!("({func} {})", args.join(" "));
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs_type: DataType = expression_type(lhs, scope, functions);
+            let rhs_type: DataType = expression_type(rhs, scope, functions);
+            let lhs_term: String = expression_generator(lhs, scope, functions);
+            let rhs_term: String = expression_generator(rhs, scope, functions);
+            if lhs_type == DataType::Number && rhs_type == DataType::Number {
+                return format!("({op} {lhs_term} {rhs_term})");
+            } else if lhs_type == DataType::Vector && rhs_type == DataType::Vector {
+                return format!("(map {op} {lhs_term} {rhs_term})");
+            }
+            // A number computed by a call or an operation is bound to 's0' once, instead of being computed again
+            // for every element
+            let (number, number_term, vector_term): (&Expr, String, String) =
+                if lhs_type == DataType::Vector {
+                    (rhs, rhs_term, lhs_term)
+                } else {
+                    (lhs, lhs_term, rhs_term)
+                };
+            let simple: bool = matches!(number, Expr::Num(_) | Expr::Id(_));
+            let element: &str = if simple { &number_term } else { "s0" };
+            let lambda: String = if lhs_type == DataType::Vector {
+                format!("(lambda (e0) ({op} e0 {element}))")
+            } else {
+                format!("(lambda (e0) ({op} {element} e0))")
+            };
+            if simple {
+                return format!("(map {lambda} {vector_term})");
+            }
+            return format!("(let ((s0 {number_term})) (map {lambda} {vector_term}))");
+        }
+        Expr::Negate(operand) => {
+            let operand_type: DataType = expression_type(operand, scope, functions);
+            let operand: String = expression_generator(operand, scope, functions);
+            if operand_type == DataType::Vector {
+                return format!("(map - {operand})");
+            }
+            return format!("(- {operand})");
+        }
+        Expr::Filter(vector, comparison, threshold) => {
+            let vector: String = expression_generator(vector, scope, functions);
+            let threshold: String = expression_generator(threshold, scope, functions);
+            let predicate: String = match comparison.as_str() {
+                "==" => format!("(= e0 {threshold})"),
+                "!=" => format!("(not (= e0 {threshold}))"),
+                _ => format!("({comparison} e0 {threshold})"),
+            };
+            return format!("(filter (lambda (e0) {predicate}) {vector})");
+        }
+        Expr::Column(table, column) => {
+            let table: String = expression_generator(table, scope, functions);
+            return match column {
+                Column::Name(name) => format!("(table-column {table} \"{name}\")"),
+                Column::Index(index) => format!("(table-column {table} {index})"),
+            };
+        }
+    }
+}
+
+// Decimals prelude defines 'format-decimals', which the values printed with a number of decimals are passed to
+// It returns the value as a string with exactly that many digits after the point, and no point for none, the way
+// the Prolog backend's '~Nf' and the interpreter print it
+const DECIMALS_PRELUDE: &str = "(define (format-decimals value decimals)
+  (let* ((scale (expt 10 decimals))
+         (digits (exact (round (* (abs value) scale))))
+         (sign (if (< value 0) \"-\" \"\"))
+         (whole (number->string (quotient digits scale)))
+         (fraction (number->string (remainder digits scale))))
+    (if (= decimals 0)
+        (string-append sign whole)
+        (string-append sign whole \".\"
+                       (make-string (- decimals (string-length fraction)) #\\0)
+                       fraction))))
+";
+
+// PrintItem Generator
+// Takes a PrintItem and returns the Scheme expression for its value, formatted if decimals are given
+fn printitem_generator(item: &PrintItem) -> String {
+    match item {
+        PrintItem::Str(str) => return str.clone(),
+        PrintItem::Id(id, None) => return id.clone(),
+        PrintItem::Id(id, Some(precision)) => return format!("(format-decimals {id} {precision})"),
+    }
+}
+
+// Scheme Generator
+// Constants, user functions that are called, initialisers, inputs and process ops become top-level definitions
+// Takes a Program and the Signatures computed by the checker
+// Returns a String with the Scheme code
+pub fn scheme_generator(program: &Program, signatures: &Signatures) -> String {
+    let mut prog_output: String = String::new();
+
+    let decimals: bool = program
+        .outputops
+        .iter()
+        .any(|(outputop, _)| match outputop {
+            OutputOp::Print(items) | OutputOp::Write { items, .. } => items
+                .iter()
+                .any(|item| matches!(item, PrintItem::Id(_, Some(_)))),
+            _ => false,
+        });
+    if decimals {
+        prog_output.push_str(DECIMALS_PRELUDE);
+    }
+
+    let mut declared: HashMap<String, DataType> = HashMap::new();
+    for datadef in &program.datadefs {
+        declared.insert(datadef.id.clone(), datadef.data_type);
+    }
+    let mut functions: HashMap<String, &Define> = HashMap::new();
+    for define in &program.defines {
+        functions.insert(define.name.clone(), define);
+    }
+
+    for constant in &program.consts {
+        let value: &str = match constant.value.as_str() {
+            "true" => "#t",
+            "false" => "#f",
+            value => value,
+        };
+        prog_output.push_str(format!("(define {} {value})\n", constant.id).as_str());
+        if is_numeric(constant) {
+            declared.insert(constant.id.clone(), DataType::Number);
+        }
+    }
+
+    for define in &program.defines {
+        if let Some(param_types) = signatures.get(&define.name) {
+            let mut params: HashMap<String, DataType> = HashMap::new();
+            for (i, param) in define.params.iter().enumerate() {
+                params.insert(param.clone(), param_types[i]);
+            }
+            prog_output.push_str(
+                format!(
+                    "(define ({} {}) {})\n",
+                    define.name,
+                    define.params.join(" "),
+                    expression_generator(&define.body, &params, &functions)
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    for datadef in &program.datadefs {
+        if let Some(init) = &datadef.init {
+            prog_output.push_str(
+                format!(
+                    "(define {} {})\n",
+                    datadef.id,
+                    expression_generator(init, &declared, &functions)
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    for inputop in &program.inputops {
+        let id: &String = &inputop.id;
+        match &inputop.source {
+            Source::Csv {
+                file,
+                header,
+                column,
+                delimiter: None,
+            } => {
+                let header: &str = boolean(*header);
+                prog_output.push_str(
+                    format!("(define {id} (read-csv {file} {header} {column}))\n").as_str(),
+                );
+            }
+            Source::Csv {
+                file,
+                header,
+                column,
+                delimiter: Some(delimiter),
+            } => {
+                let header: &str = boolean(*header);
+                prog_output.push_str(
+                    format!("(define {id} (read-dsv {file} {header} {column} {delimiter}))\n")
+                        .as_str(),
+                );
+            }
+            Source::Json {
+                file,
+                field: Some(field),
+            } => {
+                prog_output
+                    .push_str(format!("(define {id} (read-json {file} {field}))\n").as_str());
+            }
+            Source::Json { file, field: None } => {
+                prog_output.push_str(format!("(define {id} (read-json {file} #f))\n").as_str());
+            }
+            Source::Lines { file } => {
+                prog_output.push_str(format!("(define {id} (read-lines {file}))\n").as_str());
+            }
+            Source::Table { file, header } => {
+                let header: &str = boolean(*header);
+                prog_output
+                    .push_str(format!("(define {id} (read-table {file} {header}))\n").as_str());
+            }
+        }
+    }
+
+    // A call with several results returns them as Scheme multiple values, which are bound with
+    // 'define-values', the top-level counterpart of 'let-values'
+    for processop in &program.processops {
+        let expr: String = expression_generator(&processop.expr, &declared, &functions);
+        if processop.ids.len() > 1 {
+            prog_output.push_str(
+                format!("(define-values ({}) {expr})\n", processop.ids.join(" ")).as_str(),
+            );
+        } else {
+            prog_output.push_str(format!("(define {} {expr})\n", processop.ids[0]).as_str());
+        }
+    }
+
+    for (outputop, _) in &program.outputops {
+        match outputop {
+            OutputOp::Str(str_or_id) | OutputOp::Id(str_or_id) => {
+                prog_output.push_str(format!("(display {str_or_id})\n(newline)\n").as_str());
+            }
+            OutputOp::Print(items) => {
+                for item in items {
+                    prog_output
+                        .push_str(format!("(display {})\n", printitem_generator(item)).as_str());
+                }
+                prog_output.push_str("(newline)\n");
+            }
+            OutputOp::Write {
+                file,
+                items,
+                append,
+            } => {
+                let func: &str = if *append { "append-row" } else { "write-row" };
+                let items: Vec<String> = items.iter().map(printitem_generator).collect();
+                prog_output
+                    .push_str(format!("({func} {file} (list {}))\n", items.join(" ")).as_str());
+            }
+            OutputOp::Plot { x, y, file } => {
+                prog_output.push_str(format!("(plot-scatter {x} {y} {file})\n").as_str());
+            }
+            OutputOp::Histogram { values, file } => {
+                prog_output.push_str(format!("(plot-histogram {values} {file})\n").as_str());
+            }
+        }
+    }
+
+    return prog_output;
+}
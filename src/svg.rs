@@ -0,0 +1,171 @@
+// SVG chart rendering
+// Draws the scatter plots and histograms of the plot and histogram output statements as standalone SVG files
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 480.0;
+const MARGIN: f64 = 60.0;
+
+// Axis struct stores the data range shown along one axis and the spacing of its ticks
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+// Helper function
+// Takes the smallest and largest value to show and returns an Axis with round tick values covering them
+fn axis(min: f64, max: f64) -> Axis {
+    let (min, max) = if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let rough_step: f64 = (max - min) / 5.0;
+    let magnitude: f64 = 10f64.powf(rough_step.log10().floor());
+    let step: f64 = match rough_step / magnitude {
+        r if r <= 1.0 => magnitude,
+        r if r <= 2.0 => 2.0 * magnitude,
+        r if r <= 5.0 => 5.0 * magnitude,
+        _ => 10.0 * magnitude,
+    };
+    return Axis {
+        min: (min / step).floor() * step,
+        max: (max / step).ceil() * step,
+        step,
+    };
+}
+
+// Helper function
+// Takes an Axis and returns each tick value with its label, using as few decimals as the step allows
+fn ticks(axis: &Axis) -> Vec<(f64, String)> {
+    let decimals: usize = (-axis.step.log10().floor()).max(0.0) as usize;
+    let count: usize = ((axis.max - axis.min) / axis.step).round() as usize;
+    return (0..=count)
+        .map(|i| {
+            let value: f64 = axis.min + i as f64 * axis.step;
+            (value, format!("{value:.decimals$}"))
+        })
+        .collect();
+}
+
+// Helper function
+// Maps a value on the x axis to its horizontal pixel position
+fn x_pixel(axis: &Axis, value: f64) -> f64 {
+    return MARGIN + (value - axis.min) / (axis.max - axis.min) * (WIDTH - 2.0 * MARGIN);
+}
+
+// Helper function
+// Maps a value on the y axis to its vertical pixel position
+fn y_pixel(axis: &Axis, value: f64) -> f64 {
+    return HEIGHT - MARGIN - (value - axis.min) / (axis.max - axis.min) * (HEIGHT - 2.0 * MARGIN);
+}
+
+// Helper function
+// Takes the x and y Axis and returns the opening tag, background, axis lines, ticks, and labels of a chart
+fn frame(x_axis: &Axis, y_axis: &Axis) -> String {
+    let mut svg: String = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"{WIDTH}\" height=\"{HEIGHT}\" fill=\"white\"/>\n"
+    );
+    let bottom: f64 = HEIGHT - MARGIN;
+    let right: f64 = WIDTH - MARGIN;
+    svg.push_str(&format!(
+        "<line x1=\"{MARGIN}\" y1=\"{bottom}\" x2=\"{right}\" y2=\"{bottom}\" stroke=\"black\"/>\n\
+         <line x1=\"{MARGIN}\" y1=\"{MARGIN}\" x2=\"{MARGIN}\" y2=\"{bottom}\" stroke=\"black\"/>\n"
+    ));
+    for (value, label) in ticks(x_axis) {
+        let x: f64 = x_pixel(x_axis, value);
+        svg.push_str(&format!(
+            "<line x1=\"{x:.1}\" y1=\"{bottom}\" x2=\"{x:.1}\" y2=\"{}\" stroke=\"black\"/>\n\
+             <text x=\"{x:.1}\" y=\"{}\" text-anchor=\"middle\">{label}</text>\n",
+            bottom + 5.0,
+            bottom + 20.0
+        ));
+    }
+    for (value, label) in ticks(y_axis) {
+        let y: f64 = y_pixel(y_axis, value);
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{MARGIN}\" y2=\"{y:.1}\" stroke=\"black\"/>\n\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{label}</text>\n",
+            MARGIN - 5.0,
+            MARGIN - 8.0,
+            y + 4.0
+        ));
+    }
+    return svg;
+}
+
+// Scatter SVG
+// Draws the points (x[i], y[i]) and the fitted line y = intercept + slope * x across the x range
+// Takes the x and y values, the intercept, and the slope
+// Returns the SVG document as a String
+pub fn scatter_svg(x: &[f64], y: &[f64], intercept: f64, slope: f64) -> String {
+    let x_min: f64 = x.iter().cloned().fold(f64::INFINITY, f64::min);
+    let x_max: f64 = x.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let line_start: f64 = intercept + slope * x_min;
+    let line_end: f64 = intercept + slope * x_max;
+    let y_min: f64 = y.iter().cloned().fold(line_start.min(line_end), f64::min);
+    let y_max: f64 = y.iter().cloned().fold(line_start.max(line_end), f64::max);
+    let x_axis: Axis = axis(x_min, x_max);
+    let y_axis: Axis = axis(y_min, y_max);
+
+    let mut svg: String = frame(&x_axis, &y_axis);
+    for (x_value, y_value) in x.iter().zip(y) {
+        svg.push_str(&format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"steelblue\"/>\n",
+            x_pixel(&x_axis, *x_value),
+            y_pixel(&y_axis, *y_value)
+        ));
+    }
+    svg.push_str(&format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"firebrick\" stroke-width=\"2\"/>\n",
+        x_pixel(&x_axis, x_min),
+        y_pixel(&y_axis, line_start),
+        x_pixel(&x_axis, x_max),
+        y_pixel(&y_axis, line_end)
+    ));
+    svg.push_str("</svg>\n");
+    return svg;
+}
+
+// Histogram SVG
+// Sorts the values into ceil(log2(n)) + 1 equal-width bins (Sturges' rule) and draws one bar per bin
+// Takes the values
+// Returns the SVG document as a String
+pub fn histogram_svg(values: &[f64]) -> String {
+    let min: f64 = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max: f64 = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let num_bins: usize = if min == max {
+        1
+    } else {
+        (values.len() as f64).log2().ceil() as usize + 1
+    };
+    let width: f64 = if min == max {
+        1.0
+    } else {
+        (max - min) / num_bins as f64
+    };
+
+    let mut counts: Vec<usize> = vec![0; num_bins];
+    for value in values {
+        let bin: usize = (((value - min) / width) as usize).min(num_bins - 1);
+        counts[bin] += 1;
+    }
+
+    let x_axis: Axis = axis(min, min + width * num_bins as f64);
+    let y_axis: Axis = axis(0.0, *counts.iter().max().unwrap() as f64);
+
+    let mut svg: String = frame(&x_axis, &y_axis);
+    for (i, count) in counts.iter().enumerate() {
+        let left: f64 = x_pixel(&x_axis, min + i as f64 * width);
+        let right: f64 = x_pixel(&x_axis, min + (i + 1) as f64 * width);
+        let top: f64 = y_pixel(&y_axis, *count as f64);
+        svg.push_str(&format!(
+            "<rect x=\"{left:.1}\" y=\"{top:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"steelblue\" stroke=\"white\"/>\n",
+            right - left,
+            y_pixel(&y_axis, 0.0) - top
+        ));
+    }
+    svg.push_str("</svg>\n");
+    return svg;
+}